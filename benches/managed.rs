@@ -1,6 +1,7 @@
 use std::{fmt::Display, time::Duration};
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use deadpool::QueueMode;
 use tokio::task::JoinHandle;
 
 const ITERATIONS: usize = 1 << 6;
@@ -19,7 +20,20 @@ impl deadpool::Manager for Manager {
     }
 }
 
-type Pool = deadpool::Pool<Manager>;
+struct NativeManager;
+
+impl deadpool::NativeManager for NativeManager {
+    type Type = ();
+    type Error = ();
+    async fn create(&self) -> Result<Self::Type, Self::Error> {
+        Ok(())
+    }
+    async fn recycle(&self, t: Self::Type) -> Option<Self::Type> {
+        Some(t)
+    }
+}
+
+type Pool<M> = deadpool::Pool<M>;
 
 #[derive(Copy, Clone, Debug)]
 struct Config {
@@ -38,7 +52,10 @@ impl Config {
         ITERATIONS / self.workers
     }
 
-    async fn run(self, pool: Pool) {
+    async fn run<M>(self, pool: Pool<M>)
+    where
+        M: deadpool::NativeManager<Type = (), Error = ()>,
+    {
         for _ in 0..self.operations_per_worker() {
             let _obj = pool.get().await.unwrap();
             tokio::time::sleep(Duration::from_micros(1)).await; // simulate load
@@ -62,8 +79,23 @@ const CONFIGS: &[Config] = &[
     Config { workers: 32, pool_size: 32 },
 ];
 
-async fn bench_get(cfg: Config) {
-    let pool = Pool::builder(Manager).max_size(cfg.pool_size).build();
+// Many workers competing for few objects without any simulated load, so
+// most of the time is spent returning objects to the pool.
+#[rustfmt::skip]
+const CONTENTION_CONFIGS: &[Config] = &[
+    Config { workers: 64, pool_size:  2 },
+    Config { workers: 64, pool_size:  8 },
+    Config { workers: 64, pool_size: 32 },
+];
+
+async fn bench_get<M>(manager: M, cfg: Config)
+where
+    M: deadpool::NativeManager<Type = (), Error = ()> + 'static,
+{
+    let pool = Pool::builder(manager)
+        .max_size(cfg.pool_size)
+        .build()
+        .unwrap();
     let join_handles: Vec<JoinHandle<()>> = (0..cfg.workers)
         .map(|_| tokio::spawn(cfg.run(pool.clone())))
         .collect();
@@ -72,12 +104,47 @@ async fn bench_get(cfg: Config) {
     }
 }
 
+async fn bench_contention(cfg: Config, queue_mode: QueueMode) {
+    let pool = Pool::builder(NativeManager)
+        .max_size(cfg.pool_size)
+        .queue_mode(queue_mode)
+        .build()
+        .unwrap();
+    let join_handles: Vec<JoinHandle<()>> = (0..cfg.workers)
+        .map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                for _ in 0..ITERATIONS {
+                    drop(pool.get().await.unwrap());
+                }
+            })
+        })
+        .collect();
+    for join_handle in join_handles {
+        join_handle.await.unwrap();
+    }
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     let mut group = c.benchmark_group("managed");
     for &config in CONFIGS {
         group.bench_function(BenchmarkId::new("get", config), |b| {
-            b.to_async(&runtime).iter(|| bench_get(config))
+            b.to_async(&runtime).iter(|| bench_get(Manager, config))
+        });
+        group.bench_function(BenchmarkId::new("get_native", config), |b| {
+            b.to_async(&runtime)
+                .iter(|| bench_get(NativeManager, config))
+        });
+    }
+    for &config in CONTENTION_CONFIGS {
+        group.bench_function(BenchmarkId::new("contention", config), |b| {
+            b.to_async(&runtime)
+                .iter(|| bench_contention(config, QueueMode::Single))
+        });
+        group.bench_function(BenchmarkId::new("contention_sharded", config), |b| {
+            b.to_async(&runtime)
+                .iter(|| bench_contention(config, QueueMode::per_cpu()))
         });
     }
 }
@@ -32,13 +32,13 @@
 //! DEALINGS IN THE SOFTWARE.
 
 use std::boxed::Box;
-use std::cell::UnsafeCell;
+use std::fmt;
 use std::mem::MaybeUninit;
-use std::sync::atomic::{self, AtomicUsize, Ordering};
-use std::{fmt, hint, thread};
 
 use crossbeam_utils::CachePadded;
-use tokio::task::yield_now;
+
+use crate::sync::atomic::{self, AtomicUsize, Ordering};
+use crate::sync::{hint, yield_now, UnsafeCell};
 
 /// A slot in a queue.
 struct Slot<T> {
@@ -82,6 +82,24 @@ pub(crate) struct ArrayQueue<T> {
 unsafe impl<T: Send> Sync for ArrayQueue<T> {}
 unsafe impl<T: Send> Send for ArrayQueue<T> {}
 
+/// Error returned by [`ArrayQueue::push()`] handing back the value.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum PushError<T> {
+    /// The queue is full.
+    Full(T),
+
+    /// The next slot is still being written or read by another thread.
+    Busy(T),
+}
+
+impl<T> PushError<T> {
+    pub(crate) fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) | Self::Busy(value) => value,
+        }
+    }
+}
+
 enum Pause {
     Spin,
     Yield,
@@ -164,9 +182,9 @@ impl<T> ArrayQueue<T> {
                 // We updated the tail before any other thread - this slot is ours!
                 Ok(_) => {
                     // Write the value into the slot and update the stamp.
-                    unsafe {
-                        slot.value.get().write(MaybeUninit::new(value));
-                    }
+                    slot.value.with_mut(|ptr| unsafe {
+                        ptr.write(MaybeUninit::new(value));
+                    });
                     // Mark the slot as init by setting the stamp index is offset from the buffer index
                     slot.stamp.store(tail + 1, Ordering::Release);
                     Flow::Break(Ok(()))
@@ -195,22 +213,32 @@ impl<T> ArrayQueue<T> {
         }
     }
 
-    pub(crate) fn push_blocking(&self, mut value: T) -> Result<(), T> {
-        let mut step = 0;
+    /// Pushes `value` into the queue without waiting for other threads.
+    ///
+    /// Unlike [`ArrayQueue::pop()`] this never spins or yields while the
+    /// next slot is still being written or read by another thread, but
+    /// returns [`PushError::Busy`] instead. It only retries if another thread
+    /// made progress in the meantime, so it is lock-free.
+    pub(crate) fn push(&self, mut value: T) -> Result<(), PushError<T>> {
         let mut tail = self.tail.load(Ordering::Relaxed);
 
         loop {
             match self.try_push(tail, value) {
-                Flow::Break(b) => break b,
-                Flow::Continue(pause, t, v) => {
+                Flow::Break(b) => break b.map_err(PushError::Full),
+                Flow::Continue(_, Some(t), v) => {
+                    // We lost the race for the slot, so the tail moved on.
                     value = v;
-                    // pause the thread - hints that the thread should sync
-                    match pause {
-                        Pause::Yield if step > 6 => thread::yield_now(),
-                        _ => hint::spin_loop(),
+                    tail = t;
+                    hint::spin_loop();
+                }
+                Flow::Continue(_, None, v) => {
+                    // The slot is still in use unless our tail is stale.
+                    value = v;
+                    let t = self.tail.load(Ordering::Relaxed);
+                    if t == tail {
+                        break Err(PushError::Busy(value));
                     }
-                    step += 1;
-                    tail = t.unwrap_or_else(|| self.tail.load(Ordering::Relaxed));
+                    tail = t;
                 }
             }
         }
@@ -237,7 +265,7 @@ impl<T> ArrayQueue<T> {
                 // We updated the head before any other thread - this slot is ours!
                 Ok(_) => {
                     // Read the value from the slot and update the stamp.
-                    let msg = unsafe { slot.value.get().read().assume_init() };
+                    let msg = slot.value.with(|ptr| unsafe { ptr.read().assume_init() });
                     // Mark the slot as uninit by setting the stamp index equal to the buffer index
                     slot.stamp
                         .store(head.wrapping_add(self.one_lap), Ordering::Release);
@@ -283,6 +311,7 @@ impl<T> ArrayQueue<T> {
         }
     }
 
+    #[cfg(test)]
     pub(crate) fn capacity(&self) -> usize {
         self.cap
     }
@@ -319,9 +348,10 @@ impl<T> ArrayQueue<T> {
 
 impl<T> Drop for ArrayQueue<T> {
     fn drop(&mut self) {
-        // Get the index of the head.
-        let head = *self.head.get_mut();
-        let tail = *self.tail.get_mut();
+        // Get the index of the head. Relaxed is fine as `&mut self` guarantees
+        // exclusive access.
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
 
         let len = self.len_impl(head, tail);
         let head_index = head & (self.one_lap - 1);
@@ -335,12 +365,11 @@ impl<T> Drop for ArrayQueue<T> {
                 head_index + i - self.cap
             };
 
-            unsafe {
-                debug_assert!(index < self.buffer.len());
-                let slot = self.buffer.get_unchecked_mut(index);
-                let value = &mut *slot.value.get();
-                value.as_mut_ptr().drop_in_place();
-            }
+            debug_assert!(index < self.buffer.len());
+            let slot = unsafe { self.buffer.get_unchecked(index) };
+            slot.value.with_mut(|ptr| unsafe {
+                (*ptr).as_mut_ptr().drop_in_place();
+            });
         }
     }
 }
@@ -350,3 +379,330 @@ impl<T> fmt::Debug for ArrayQueue<T> {
         f.pad("ArrayQueue { .. }")
     }
 }
+
+/// Model checked tests exploring all interleavings of the queue operations.
+///
+/// Run with `RUSTFLAGS="--cfg loom" cargo test --release --lib array_queue`.
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use loom::{
+        future::block_on,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+    };
+
+    use super::{ArrayQueue, PushError};
+
+    /// Counts how often values of this type have been dropped.
+    struct DropCounter(Arc<AtomicUsize>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            let _ = self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn push_pop() {
+        loom::model(|| {
+            let queue = Arc::new(ArrayQueue::new(2));
+
+            let producer = {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    queue.push(1).unwrap();
+                    queue.push(2).unwrap();
+                })
+            };
+
+            let first = block_on(queue.pop());
+            producer.join().unwrap();
+
+            // Values are delivered exactly once and in order.
+            let mut values: Vec<_> = first.into_iter().collect();
+            while let Some(value) = block_on(queue.pop()) {
+                values.push(value);
+            }
+            assert_eq!(values, [1, 2]);
+        });
+    }
+
+    #[test]
+    fn concurrent_pop() {
+        loom::model(|| {
+            let queue = Arc::new(ArrayQueue::new(2));
+            queue.push(1).unwrap();
+            queue.push(2).unwrap();
+
+            let consumer = {
+                let queue = queue.clone();
+                thread::spawn(move || block_on(queue.pop()))
+            };
+
+            let a = block_on(queue.pop()).unwrap();
+            let b = consumer.join().unwrap().unwrap();
+            assert_ne!(a, b);
+            assert_eq!(a + b, 3);
+            assert_eq!(block_on(queue.pop()), None);
+        });
+    }
+
+    #[test]
+    fn concurrent_push_full() {
+        loom::model(|| {
+            let queue = Arc::new(ArrayQueue::new(1));
+
+            let producer = {
+                let queue = queue.clone();
+                thread::spawn(move || queue.push(1))
+            };
+
+            let a = queue.push(2);
+            let b = producer.join().unwrap();
+
+            // Exactly one value fits and the other one is handed back.
+            let (pushed, rejected) = match (a, b) {
+                (Ok(()), Err(err)) => (2, err.into_inner()),
+                (Err(err), Ok(())) => (1, err.into_inner()),
+                res => panic!("unexpected results: {:?}", res),
+            };
+            assert_eq!(pushed + rejected, 3);
+            assert_eq!(block_on(queue.pop()), Some(pushed));
+        });
+    }
+
+    #[test]
+    fn push_never_waits() {
+        loom::model(|| {
+            let queue = Arc::new(ArrayQueue::new(1));
+            queue.push(0).unwrap();
+
+            let consumer = {
+                let queue = queue.clone();
+                thread::spawn(move || block_on(queue.pop()))
+            };
+
+            // The only slot is either still occupied, currently being read or
+            // free again.
+            let res = queue.push(1);
+            assert_eq!(consumer.join().unwrap(), Some(0));
+            match res {
+                Ok(()) => assert_eq!(block_on(queue.pop()), Some(1)),
+                Err(PushError::Full(1) | PushError::Busy(1)) => {
+                    assert_eq!(block_on(queue.pop()), None);
+                }
+                Err(err) => panic!("unexpected error: {:?}", err),
+            }
+        });
+    }
+
+    #[test]
+    fn len_within_bounds() {
+        loom::model(|| {
+            let queue = Arc::new(ArrayQueue::new(2));
+            queue.push(0).unwrap();
+
+            let producer = {
+                let queue = queue.clone();
+                thread::spawn(move || queue.push(1).unwrap())
+            };
+            let consumer = {
+                let queue = queue.clone();
+                thread::spawn(move || block_on(queue.pop()))
+            };
+
+            assert!(queue.len() <= queue.capacity());
+
+            producer.join().unwrap();
+            assert_eq!(consumer.join().unwrap(), Some(0));
+            assert_eq!(queue.len(), 1);
+        });
+    }
+
+    #[test]
+    fn drop_remaining() {
+        loom::model(|| {
+            let drops = Arc::new(AtomicUsize::new(0));
+            let queue = Arc::new(ArrayQueue::new(2));
+            queue.push(DropCounter(drops.clone())).ok().unwrap();
+
+            let producer = {
+                let queue = queue.clone();
+                let drops = drops.clone();
+                thread::spawn(move || {
+                    queue.push(DropCounter(drops)).ok().unwrap();
+                })
+            };
+            let consumer = {
+                let queue = queue.clone();
+                thread::spawn(move || drop(block_on(queue.pop())))
+            };
+
+            producer.join().unwrap();
+            consumer.join().unwrap();
+            assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+            // The last handle drops the queue along with its remaining value.
+            drop(Arc::try_unwrap(queue).ok().unwrap());
+            assert_eq!(drops.load(Ordering::Relaxed), 2);
+        });
+    }
+}
+
+/// Tests which are small enough to be run under Miri to detect undefined
+/// behavior, e.g. via `cargo +nightly miri test --lib array_queue`.
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+        thread,
+    };
+
+    use super::{ArrayQueue, PushError};
+
+    /// Polls the given future to completion without a runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        const VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    /// Counts how often values of this type have been dropped.
+    struct DropCounter(Arc<AtomicUsize>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            let _ = self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn push_pop() {
+        let queue = ArrayQueue::new(3);
+        assert_eq!(queue.capacity(), 3);
+        assert_eq!(queue.len(), 0);
+        assert_eq!(block_on(queue.pop()), None);
+
+        queue.push(1).unwrap();
+        queue.push(2).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(block_on(queue.pop()), Some(1));
+        assert_eq!(block_on(queue.pop()), Some(2));
+        assert_eq!(block_on(queue.pop()), None);
+    }
+
+    #[test]
+    fn full() {
+        let queue = ArrayQueue::new(2);
+        queue.push(1).unwrap();
+        queue.push(2).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.push(3), Err(PushError::Full(3)));
+        assert_eq!(block_on(queue.pop()), Some(1));
+        queue.push(3).unwrap();
+        assert_eq!(queue.push(4), Err(PushError::Full(4)));
+    }
+
+    #[test]
+    fn wraparound() {
+        let queue = ArrayQueue::new(3);
+        for i in 0..10 {
+            queue.push(i).unwrap();
+            queue.push(i + 100).unwrap();
+            assert_eq!(queue.len(), 2);
+            assert_eq!(block_on(queue.pop()), Some(i));
+            assert_eq!(block_on(queue.pop()), Some(i + 100));
+            assert_eq!(queue.len(), 0);
+        }
+    }
+
+    #[test]
+    fn drop_remaining() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let queue = ArrayQueue::new(3);
+        // Move the head so the remaining values wrap around the buffer.
+        for _ in 0..2 {
+            queue.push(DropCounter(drops.clone())).ok().unwrap();
+            drop(block_on(queue.pop()));
+        }
+        for _ in 0..3 {
+            queue.push(DropCounter(drops.clone())).ok().unwrap();
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(queue);
+        assert_eq!(drops.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn concurrent() {
+        const THREADS: usize = 2;
+        const COUNT: usize = if cfg!(miri) { 50 } else { 5_000 };
+
+        let queue = Arc::new(ArrayQueue::new(2));
+        let popped = Arc::new(AtomicUsize::new(0));
+        let sum = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..THREADS)
+            .flat_map(|_| {
+                let producer = {
+                    let queue = queue.clone();
+                    thread::spawn(move || {
+                        for i in 0..COUNT {
+                            let mut value = i;
+                            while let Err(err) = queue.push(value) {
+                                value = err.into_inner();
+                                thread::yield_now();
+                            }
+                        }
+                    })
+                };
+                let consumer = {
+                    let queue = queue.clone();
+                    let popped = popped.clone();
+                    let sum = sum.clone();
+                    thread::spawn(move || {
+                        while popped.load(Ordering::Relaxed) < THREADS * COUNT {
+                            if let Some(value) = block_on(queue.pop()) {
+                                let _ = sum.fetch_add(value, Ordering::Relaxed);
+                                let _ = popped.fetch_add(1, Ordering::Relaxed);
+                            } else {
+                                thread::yield_now();
+                            }
+                        }
+                    })
+                };
+                [producer, consumer]
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(popped.load(Ordering::Relaxed), THREADS * COUNT);
+        assert_eq!(
+            sum.load(Ordering::Relaxed),
+            THREADS * COUNT * (COUNT - 1) / 2
+        );
+        assert_eq!(queue.len(), 0);
+    }
+}
@@ -1,6 +1,12 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use super::{Manager, Pool, PoolConfig};
+use tokio::runtime::Handle;
+
+use super::{
+    hooks::{Hook, Hooks},
+    leak::LeakCallback,
+    BuildError, LeakReport, NativeManager, Pool, PoolConfig, QueueMode,
+};
 
 /// Builder for [`Pool`]s.
 ///
@@ -9,30 +15,70 @@ use super::{Manager, Pool, PoolConfig};
 #[derive(Debug)]
 pub struct PoolBuilder<M>
 where
-    M: Manager,
+    M: NativeManager,
 {
     pub(crate) manager: M,
     pub(crate) config: PoolConfig,
+    pub(crate) hooks: Hooks<M>,
+    pub(crate) leak_callback: Option<Arc<LeakCallback>>,
 }
 
 impl<M> PoolBuilder<M>
 where
-    M: Manager,
+    M: NativeManager,
 {
     pub(crate) fn new(manager: M) -> Self {
         Self {
             manager,
             config: PoolConfig::default(),
+            hooks: Hooks::default(),
+            leak_callback: None,
         }
     }
 
     /// Builds the [`Pool`].
     ///
+    /// If a [`PoolConfig::timeout`] or [`PoolConfig::leak_threshold`] is
+    /// configured, this has to be called within a tokio runtime, which the
+    /// [`Pool`] needs for timeouts and leak detection. When building it in a
+    /// synchronous `main` before running it within [`Runtime::block_on()`],
+    /// enter the runtime first:
+    ///
+    /// ```rust
+    /// # use std::{convert::Infallible, time::Duration};
+    /// # struct Manager;
+    /// # impl deadpool::NativeManager for Manager {
+    /// #     type Type = usize;
+    /// #     type Error = Infallible;
+    /// #     async fn create(&self) -> Result<usize, Infallible> { Ok(0) }
+    /// #     async fn recycle(&self, obj: usize) -> Option<usize> { Some(obj) }
+    /// # }
+    /// let runtime = tokio::runtime::Runtime::new().unwrap();
+    /// let pool = {
+    ///     let _guard = runtime.enter();
+    ///     deadpool::Pool::builder(Manager)
+    ///         .timeout(Some(Duration::from_secs(5)))
+    ///         .build()
+    ///         .unwrap()
+    /// };
+    /// runtime.block_on(async {
+    ///     let obj = pool.get().await.unwrap();
+    /// });
+    /// ```
+    ///
     /// # Errors
     ///
     /// See [`BuildError`] for details.
-    pub fn build(self) -> Pool<M> {
-        Pool::from_builder(self)
+    ///
+    /// [`Runtime::block_on()`]: tokio::runtime::Runtime::block_on
+    pub fn build(self) -> Result<Pool<M>, BuildError> {
+        self.config.validate()?;
+        let needs_runtime = self.config.leak_threshold.is_some()
+            || self.config.timeout.is_some_and(|t| !t.is_zero());
+        if needs_runtime && Handle::try_current().is_err() {
+            return Err(BuildError::NoRuntime);
+        }
+        Ok(Pool::from_builder(self))
     }
 
     /// Sets a [`PoolConfig`] to build the [`Pool`] with.
@@ -52,4 +98,65 @@ where
         self.config.timeout = value;
         self
     }
+
+    /// Sets the [`PoolConfig::leak_threshold`].
+    pub fn leak_threshold(mut self, value: Option<Duration>) -> Self {
+        self.config.leak_threshold = value;
+        self
+    }
+
+    /// Sets the [`PoolConfig::queue_mode`].
+    pub fn queue_mode(mut self, value: QueueMode) -> Self {
+        self.config.queue_mode = value;
+        self
+    }
+
+    /// Sets the [`PoolConfig::thread_cache`].
+    pub fn thread_cache(mut self, value: usize) -> Self {
+        self.config.thread_cache = value;
+        self
+    }
+
+    /// Sets the callback which is invoked for every [`Object`] held for
+    /// longer than the [`PoolConfig::leak_threshold`].
+    ///
+    /// The callback is called from within a tokio task and should not block.
+    ///
+    /// [`Object`]: crate::Object
+    pub fn on_leak(mut self, f: impl Fn(&LeakReport) + Sync + Send + 'static) -> Self {
+        self.leak_callback = Some(Arc::new(LeakCallback::new(f)));
+        self
+    }
+
+    /// Attaches a `post_create` hook.
+    ///
+    /// The given `hook` will be called each time right after a new object has
+    /// been created. If it fails, the object is dropped and [`Pool::get()`]
+    /// returns a [`PoolError::PostCreateHook`].
+    ///
+    /// [`PoolError::PostCreateHook`]: crate::PoolError::PostCreateHook
+    pub fn post_create(mut self, hook: impl Into<Hook<M>>) -> Self {
+        self.hooks.post_create.push(hook.into());
+        self
+    }
+
+    /// Attaches a `pre_recycle` hook.
+    ///
+    /// The given `hook` will be called each time right before an idle object
+    /// is passed to [`NativeManager::recycle()`]. If it fails, the object is
+    /// discarded and [`Pool::get()`] carries on with the next one.
+    pub fn pre_recycle(mut self, hook: impl Into<Hook<M>>) -> Self {
+        self.hooks.pre_recycle.push(hook.into());
+        self
+    }
+
+    /// Attaches a `post_recycle` hook.
+    ///
+    /// The given `hook` will be called each time right after an object has
+    /// been recycled successfully. If it fails, the object is discarded and
+    /// [`Pool::get()`] carries on with the next one.
+    pub fn post_recycle(mut self, hook: impl Into<Hook<M>>) -> Self {
+        self.hooks.post_recycle.push(hook.into());
+        self
+    }
 }
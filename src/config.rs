@@ -1,9 +1,18 @@
-use std::time::Duration;
+use std::{env, error::Error, fmt, time::Duration};
+
+use crate::{duration, BuildError};
 
 /// [`Pool`] configuration.
 ///
 /// [`Pool`]: super::Pool
+///
+/// With the `serde` feature enabled, this can be deserialized with all fields
+/// being optional and defaulting to [`PoolConfig::default()`]. Durations are
+/// either strings consisting of a number and a unit (`ns`, `us`, `ms`, `s`,
+/// `m` or `h`), e.g. `"30s"` or `"1.5m"`, or plain numbers of milliseconds.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct PoolConfig {
     /// Maximum size of the [`Pool`].
     ///
@@ -13,18 +22,138 @@ pub struct PoolConfig {
     /// Timeouts of the [`Pool`].
     ///
     /// [`Pool`]: super::Pool
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "crate::duration::serde_option",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
     pub timeout: Option<Duration>,
+
+    /// Duration after which a checked out [`Object`] is reported as leaked.
+    ///
+    /// Leaked objects are counted in [`PoolMetrics::leak_count`] and passed
+    /// to the callback registered using [`PoolBuilder::on_leak()`].
+    ///
+    /// [`Object`]: super::Object
+    /// [`PoolMetrics::leak_count`]: super::PoolMetrics::leak_count
+    /// [`PoolBuilder::on_leak()`]: super::PoolBuilder::on_leak
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "crate::duration::serde_option",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub leak_threshold: Option<Duration>,
+
+    /// How idle objects are stored.
+    pub queue_mode: QueueMode,
+
+    /// Number of idle objects cached per thread, which is disabled if zero.
+    ///
+    /// A cached object is handed out to the next task calling
+    /// [`Pool::get()`] on the same thread without going through the shared
+    /// queue and semaphore. This speeds up pools of cheap objects which are
+    /// used only briefly. Tasks waiting for an object on other threads take
+    /// them from the cache as well, so cached objects are never stranded.
+    ///
+    /// [`Pool::get()`]: super::Pool::get
+    pub thread_cache: usize,
 }
 
 impl PoolConfig {
-    /// Creates a new [`PoolConfig`] without any timeouts and with the provided
-    /// `max_size`.
+    /// Creates a new [`PoolConfig`] without any timeouts, without leak
+    /// detection, using a single idle queue without thread cache and with the
+    /// provided `max_size`.
     #[must_use]
     pub fn new(max_size: usize) -> Self {
         Self {
             max_size,
             timeout: None,
+            leak_threshold: None,
+            queue_mode: QueueMode::Single,
+            thread_cache: 0,
+        }
+    }
+
+    /// Creates a new [`PoolConfig`] from environment variables named after
+    /// its fields and prefixed with `prefix` and `__`, e.g. with the prefix
+    /// `DB_POOL`:
+    ///
+    /// | Variable                          | Example                      |
+    /// |-----------------------------------|------------------------------|
+    /// | `DB_POOL__MAX_SIZE`               | `16`                         |
+    /// | `DB_POOL__TIMEOUT`                | `30s`, `1.5m` or `250` (ms)  |
+    /// | `DB_POOL__LEAK_THRESHOLD`         | `5m`                         |
+    /// | `DB_POOL__QUEUE_MODE`             | `single` or `sharded`        |
+    /// | `DB_POOL__QUEUE_MODE__SHARDS`     | `8`                          |
+    /// | `DB_POOL__THREAD_CACHE`           | `2`                          |
+    ///
+    /// Durations consist of a number and an optional unit (`ns`, `us`, `ms`,
+    /// `s`, `m` or `h`) defaulting to milliseconds. A `sharded` queue without
+    /// `QUEUE_MODE__SHARDS` uses [`QueueMode::per_cpu()`]. Variables which
+    /// aren't set keep the values of [`PoolConfig::default()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FromEnvError`] naming the first variable which is set but
+    /// malformed.
+    pub fn from_env(prefix: &str) -> Result<Self, FromEnvError> {
+        let var = |name: &str| EnvVar::read(format!("{prefix}__{name}"));
+        let mut config = Self::default();
+        if let Some(max_size) = var("MAX_SIZE")? {
+            config.max_size = max_size.parse_with(str::parse)?;
+        }
+        if let Some(timeout) = var("TIMEOUT")? {
+            config.timeout = Some(timeout.parse_with(duration::parse)?);
+        }
+        if let Some(leak_threshold) = var("LEAK_THRESHOLD")? {
+            config.leak_threshold = Some(leak_threshold.parse_with(duration::parse)?);
         }
+        let mut shards = var("QUEUE_MODE__SHARDS")?;
+        if let Some(queue_mode) = var("QUEUE_MODE")? {
+            config.queue_mode = match queue_mode.value.as_str() {
+                "single" => QueueMode::Single,
+                "sharded" => match shards.take() {
+                    Some(shards) => QueueMode::Sharded {
+                        shards: shards.parse_with(str::parse)?,
+                    },
+                    None => QueueMode::per_cpu(),
+                },
+                _ => return Err(queue_mode.error("expected `single` or `sharded`".into())),
+            };
+        }
+        if let Some(shards) = shards {
+            return Err(shards.error("requires `QUEUE_MODE` to be `sharded`".into()));
+        }
+        if let Some(thread_cache) = var("THREAD_CACHE")? {
+            config.thread_cache = thread_cache.parse_with(str::parse)?;
+        }
+        Ok(config)
+    }
+
+    /// Checks this [`PoolConfig`] for settings a [`Pool`] can't be built with.
+    ///
+    /// [`Pool`]: super::Pool
+    pub(crate) fn validate(&self) -> Result<(), BuildError> {
+        if self.max_size == 0 {
+            return Err(BuildError::ZeroMaxSize);
+        }
+        if self.leak_threshold == Some(Duration::ZERO) {
+            return Err(BuildError::ZeroLeakThreshold);
+        }
+        if self.queue_mode == (QueueMode::Sharded { shards: 0 }) {
+            return Err(BuildError::ZeroShards);
+        }
+        if self.thread_cache > self.max_size {
+            return Err(BuildError::ThreadCacheExceedsMaxSize {
+                thread_cache: self.thread_cache,
+                max_size: self.max_size,
+            });
+        }
+        Ok(())
     }
 }
 
@@ -35,3 +164,128 @@ impl Default for PoolConfig {
         Self::new(num_cpus::get_physical() * 4)
     }
 }
+
+/// Mode of storing idle objects in a [`Pool`].
+///
+/// [`Pool`]: super::Pool
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum QueueMode {
+    /// All idle objects are stored in a single queue shared by all threads.
+    #[default]
+    Single,
+
+    /// Idle objects are stored in one of multiple `shards` depending on the
+    /// thread returning them.
+    ///
+    /// Threads take idle objects from their own shard first and only steal
+    /// them from the other shards if it's empty. This reduces contention on
+    /// pools used by many threads at once at the cost of allocating a queue
+    /// of `max_size` slots per shard. The [`PoolConfig::max_size`] is still
+    /// enforced for the whole [`Pool`].
+    ///
+    /// [`Pool`]: super::Pool
+    Sharded {
+        /// Number of shards, which should be close to the number of threads
+        /// using the [`Pool`].
+        ///
+        /// [`Pool`]: super::Pool
+        shards: usize,
+    },
+}
+
+impl QueueMode {
+    /// Creates a [`QueueMode::Sharded`] with one shard per logical CPU.
+    #[must_use]
+    pub fn per_cpu() -> Self {
+        Self::Sharded {
+            shards: num_cpus::get(),
+        }
+    }
+
+    pub(crate) fn shards(self) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::Sharded { shards } => shards,
+        }
+    }
+}
+
+/// Set environment variable read by [`PoolConfig::from_env()`].
+struct EnvVar {
+    name: String,
+    value: String,
+}
+
+impl EnvVar {
+    fn read(name: String) -> Result<Option<Self>, FromEnvError> {
+        match env::var(&name) {
+            Ok(value) => Ok(Some(Self { name, value })),
+            Err(env::VarError::NotPresent) => Ok(None),
+            Err(env::VarError::NotUnicode(value)) => Err(FromEnvError {
+                name,
+                value: value.to_string_lossy().into_owned(),
+                source: "not valid unicode".into(),
+            }),
+        }
+    }
+
+    fn parse_with<T, E>(self, parse: impl FnOnce(&str) -> Result<T, E>) -> Result<T, FromEnvError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        match parse(self.value.trim()) {
+            Ok(value) => Ok(value),
+            Err(e) => Err(self.error(e.into())),
+        }
+    }
+
+    fn error(self, source: Box<dyn Error + Send + Sync>) -> FromEnvError {
+        FromEnvError {
+            name: self.name,
+            value: self.value,
+            source,
+        }
+    }
+}
+
+/// Error returned by [`PoolConfig::from_env()`] if an environment variable is
+/// malformed.
+#[derive(Debug)]
+pub struct FromEnvError {
+    name: String,
+    value: String,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl FromEnvError {
+    /// Returns the name of the malformed environment variable.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the malformed value, which is converted lossily if it wasn't
+    /// valid unicode.
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for FromEnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid value `{}` of environment variable `{}`: {}",
+            self.value, self.name, self.source
+        )
+    }
+}
+
+impl Error for FromEnvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.source)
+    }
+}
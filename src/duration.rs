@@ -0,0 +1,169 @@
+//! Human-readable [`Duration`]s like `30s` or `1.5h` used in configurations.
+
+use std::{convert::TryFrom, fmt, time::Duration};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Error returned when parsing a malformed [`Duration`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ParseDurationError(String);
+
+impl fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid duration `{}`, expected a number with an optional unit \
+             (`ns`, `us`, `ms`, `s`, `m` or `h`) defaulting to milliseconds",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+/// Parses a [`Duration`] consisting of a decimal number and an optional unit,
+/// e.g. `30s`, `1.5 m` or `250`. Numbers without a unit are milliseconds.
+pub(crate) fn parse(s: &str) -> Result<Duration, ParseDurationError> {
+    let err = || ParseDurationError(s.to_owned());
+    let trimmed = s.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let unit_nanos: u128 = match unit.trim() {
+        "ns" => 1,
+        "us" | "µs" => 1_000,
+        "" | "ms" => 1_000_000,
+        "s" => NANOS_PER_SEC,
+        "m" | "min" => 60 * NANOS_PER_SEC,
+        "h" => 60 * 60 * NANOS_PER_SEC,
+        _ => return Err(err()),
+    };
+
+    let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+    if int.is_empty() && frac.is_empty() || frac.contains('.') {
+        return Err(err());
+    }
+    let int: u128 = if int.is_empty() {
+        0
+    } else {
+        int.parse().map_err(|_| err())?
+    };
+    let mut nanos = int.checked_mul(unit_nanos).ok_or_else(err)?;
+    // Fractions below a nanosecond are truncated.
+    let mut scale = unit_nanos;
+    for digit in frac.chars() {
+        scale /= 10;
+        let digit = u128::from(digit.to_digit(10).ok_or_else(err)?);
+        nanos = nanos.checked_add(digit * scale).ok_or_else(err)?;
+    }
+
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| err())?;
+    // The remainder is always less than `NANOS_PER_SEC`.
+    #[allow(clippy::cast_possible_truncation)]
+    let subsec_nanos = (nanos % NANOS_PER_SEC) as u32;
+    Ok(Duration::new(secs, subsec_nanos))
+}
+
+/// Formats a [`Duration`] using the largest unit which represents it exactly,
+/// so it can be parsed by [`parse()`] again.
+#[cfg(feature = "serde")]
+pub(crate) fn format(d: Duration) -> String {
+    let subsec_nanos = d.subsec_nanos();
+    if subsec_nanos == 0 {
+        format!("{}s", d.as_secs())
+    } else if subsec_nanos % 1_000_000 == 0 {
+        format!("{}ms", d.as_millis())
+    } else if subsec_nanos % 1_000 == 0 {
+        format!("{}us", d.as_micros())
+    } else {
+        format!("{}ns", d.as_nanos())
+    }
+}
+
+/// (De)serializes an `Option<Duration>` as human-readable string or as
+/// number of milliseconds.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_option {
+    use std::{convert::TryFrom, fmt, time::Duration};
+
+    use serde::{de, Deserializer, Serializer};
+
+    #[allow(clippy::ref_option)]
+    pub(crate) fn serialize<S: Serializer>(
+        value: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(d) => serializer.serialize_some(&super::format(*d)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor)
+    }
+
+    struct OptionVisitor;
+
+    impl<'de> de::Visitor<'de> for OptionVisitor {
+        type Value = Option<Duration>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a duration like \"30s\" or a number of milliseconds")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(DurationVisitor).map(Some)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            DurationVisitor.visit_u64(v).map(Some)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            DurationVisitor.visit_i64(v).map(Some)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            DurationVisitor.visit_str(v).map(Some)
+        }
+    }
+
+    struct DurationVisitor;
+
+    impl de::Visitor<'_> for DurationVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a duration like \"30s\" or a number of milliseconds")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(Duration::from_millis(v))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            u64::try_from(v)
+                .map(Duration::from_millis)
+                .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            super::parse(v).map_err(E::custom)
+        }
+    }
+}
@@ -1,10 +1,16 @@
-use std::fmt;
+use std::{error::Error, fmt, iter};
+
+use crate::{HookError, NativeManager};
 
 /// Possible steps causing the timeout in an error returned by [`Pool::get()`]
 /// method.
 ///
+/// It is also the [`Error::source()`] of a [`PoolError::Timeout`], so it can
+/// be recovered by [`TimeoutType::find()`] once the [`PoolError`] has been
+/// converted into a `Box<dyn Error + Send + Sync>`.
+///
 /// [`Pool::get()`]: super::Pool::get
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum TimeoutType {
     /// Timeout happened while waiting for a slot to become available.
     Wait,
@@ -16,6 +22,35 @@ pub enum TimeoutType {
     Recycle,
 }
 
+impl TimeoutType {
+    /// Searches the given `error` and its [`Error::source()`] chain for a
+    /// [`TimeoutType`].
+    ///
+    /// This works regardless of the backend error type of the [`PoolError`]
+    /// and of any errors wrapping it.
+    #[must_use]
+    pub fn find(error: &(dyn Error + 'static)) -> Option<Self> {
+        iter::successors(Some(error), |&e| e.source())
+            .find_map(|e| e.downcast_ref::<Self>())
+            .copied()
+    }
+}
+
+impl fmt::Display for TimeoutType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wait => write!(
+                f,
+                "Timeout occurred while waiting for a slot to become available"
+            ),
+            Self::Create => write!(f, "Timeout occurred while creating a new object"),
+            Self::Recycle => write!(f, "Timeout occurred while recycling an object"),
+        }
+    }
+}
+
+impl Error for TimeoutType {}
+
 /// Possible errors returned by [`Pool::get()`] method.
 ///
 /// [`Pool::get()`]: super::Pool::get
@@ -24,43 +59,233 @@ pub enum PoolError<E> {
     /// Timeout happened.
     Timeout(TimeoutType),
 
-    /// Backend reported an error.
+    /// Backend reported an error while creating a new object.
+    ///
+    /// Failing to recycle an object isn't an error, as the object is just
+    /// discarded and replaced by another one, while errors of `post_create`
+    /// hooks are reported as [`PoolError::PostCreateHook`]. Both are counted
+    /// by phase in [`PoolMetrics`] though.
+    ///
+    /// There is intentionally no `From<E>` conversion into this variant, as
+    /// it would make any error look like a failure to create an object. Wrap
+    /// errors explicitly using `PoolError::Backend` instead.
+    ///
+    /// [`PoolMetrics`]: super::PoolMetrics
     Backend(E),
 
     /// [`Pool`] has been closed.
     ///
     /// [`Pool`]: super::Pool
     Closed,
+
+    /// A `post_create` hook reported an error.
+    PostCreateHook(HookError<E>),
+
+    /// [`Pool::get_many()`] was called with more objects than the [`Pool`]
+    /// can hold, e.g. because it has been shrunk by [`Pool::reconfigure()`].
+    ///
+    /// This is also returned for more than [`u32::MAX`] objects, which can't
+    /// be acquired at once regardless of the maximum size.
+    ///
+    /// [`Pool`]: super::Pool
+    /// [`Pool::get_many()`]: super::Pool::get_many
+    /// [`Pool::reconfigure()`]: super::Pool::reconfigure
+    ExceedsMaxSize {
+        /// Number of objects requested.
+        requested: usize,
+
+        /// [`Status::max_size`] of the [`Pool`] at the time of the call.
+        ///
+        /// [`Pool`]: super::Pool
+        /// [`Status::max_size`]: super::Status::max_size
+        max_size: usize,
+    },
 }
 
-impl<E> From<E> for PoolError<E> {
-    fn from(e: E) -> Self {
-        Self::Backend(e)
+impl<E> PoolError<E> {
+    /// Returns whether this is a [`PoolError::Timeout`].
+    #[must_use]
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout(_))
+    }
+
+    /// Returns whether this is a [`PoolError::Closed`].
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed)
+    }
+
+    /// Returns whether retrying the failed operation later might succeed.
+    ///
+    /// Timeouts are retryable, while a closed [`Pool`] and requesting more
+    /// objects than it can hold are not. Backend errors,
+    /// including the ones reported by `post_create` hooks, are classified by
+    /// [`NativeManager::is_transient()`] of the given `manager`, which is
+    /// usually [`Pool::manager()`]. Other hook errors are permanent.
+    ///
+    /// The `manager` has to be passed in because a [`PoolError`] only carries
+    /// the backend error itself. Where the [`Pool`] is at hand,
+    /// [`Pool::is_retryable()`] does this for you.
+    ///
+    /// [`Pool`]: super::Pool
+    /// [`Pool::is_retryable()`]: super::Pool::is_retryable
+    /// [`Pool::manager()`]: super::Pool::manager
+    #[must_use]
+    pub fn is_retryable<M: NativeManager<Error = E>>(&self, manager: &M) -> bool {
+        match self {
+            Self::Timeout(_) => true,
+            Self::Closed | Self::ExceedsMaxSize { .. } => false,
+            Self::Backend(e) | Self::PostCreateHook(HookError::Backend(e)) => {
+                manager.is_transient(e)
+            }
+            Self::PostCreateHook(HookError::Message(_) | HookError::StaticMessage(_)) => false,
+        }
+    }
+
+    /// Converts the backend error using `f`, leaving everything else as is.
+    ///
+    /// This allows unifying the errors of pools with different backend error
+    /// types:
+    ///
+    /// ```rust
+    /// use std::error::Error;
+    ///
+    /// use deadpool::{PoolError, TimeoutType};
+    ///
+    /// fn unify<E: Error + Send + Sync + 'static>(
+    ///     err: PoolError<E>,
+    /// ) -> PoolError<Box<dyn Error + Send + Sync>> {
+    ///     err.map_backend(|e| e.into())
+    /// }
+    ///
+    /// let err = unify(PoolError::<std::io::Error>::Timeout(TimeoutType::Wait));
+    /// assert!(matches!(err, PoolError::Timeout(TimeoutType::Wait)));
+    /// ```
+    pub fn map_backend<F>(self, f: impl FnOnce(E) -> F) -> PoolError<F> {
+        match self {
+            Self::Timeout(tt) => PoolError::Timeout(tt),
+            Self::Backend(e) => PoolError::Backend(f(e)),
+            Self::Closed => PoolError::Closed,
+            Self::PostCreateHook(e) => PoolError::PostCreateHook(e.map_backend(f)),
+            Self::ExceedsMaxSize {
+                requested,
+                max_size,
+            } => PoolError::ExceedsMaxSize {
+                requested,
+                max_size,
+            },
+        }
     }
 }
 
 impl<E: fmt::Display> fmt::Display for PoolError<E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Timeout(tt) => match tt {
-                TimeoutType::Wait => write!(
-                    f,
-                    "Timeout occurred while waiting for a slot to become available"
-                ),
-                TimeoutType::Create => write!(f, "Timeout occurred while creating a new object"),
-                TimeoutType::Recycle => write!(f, "Timeout occurred while recycling an object"),
-            },
-            Self::Backend(e) => write!(f, "Error occurred while creating a new object: {}", e),
+            Self::Timeout(tt) => write!(f, "{tt}"),
+            Self::Backend(e) => write!(f, "Error occurred while creating a new object: {e}"),
             Self::Closed => write!(f, "Pool has been closed"),
+            Self::PostCreateHook(e) => write!(f, "`post_create` hook failed: {e}"),
+            Self::ExceedsMaxSize {
+                requested,
+                max_size,
+            } => write!(
+                f,
+                "Cannot get {requested} objects from a pool with a maximum size of {max_size}"
+            ),
         }
     }
 }
 
-impl<E: std::error::Error + 'static> std::error::Error for PoolError<E> {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+/// Together with the standard library's blanket conversion, this allows
+/// converting a [`PoolError`] into a `Box<dyn Error + Send + Sync>` using `?`.
+/// The [`TimeoutType`] can still be recovered by [`TimeoutType::find()`].
+impl<E: Error + 'static> Error for PoolError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            Self::Timeout(_) | Self::Closed => None,
+            Self::Timeout(tt) => Some(tt),
+            Self::Closed | Self::ExceedsMaxSize { .. } => None,
             Self::Backend(e) => Some(e),
+            Self::PostCreateHook(e) => Some(e),
         }
     }
 }
+
+/// Possible errors returned by [`PoolBuilder::build()`] method.
+///
+/// [`PoolBuilder::build()`]: super::PoolBuilder::build
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BuildError {
+    /// [`PoolConfig::max_size`] is zero, so no object could ever be created.
+    ///
+    /// [`PoolConfig::max_size`]: super::PoolConfig::max_size
+    ZeroMaxSize,
+
+    /// [`PoolConfig::leak_threshold`] is zero, so every object would be
+    /// reported as leaked right away.
+    ///
+    /// [`PoolConfig::leak_threshold`]: super::PoolConfig::leak_threshold
+    ZeroLeakThreshold,
+
+    /// [`QueueMode::Sharded`] has zero shards.
+    ///
+    /// [`QueueMode::Sharded`]: super::QueueMode::Sharded
+    ZeroShards,
+
+    /// [`PoolConfig::thread_cache`] is larger than [`PoolConfig::max_size`],
+    /// so it could never be filled.
+    ///
+    /// [`PoolConfig::thread_cache`]: super::PoolConfig::thread_cache
+    /// [`PoolConfig::max_size`]: super::PoolConfig::max_size
+    ThreadCacheExceedsMaxSize {
+        /// Configured [`PoolConfig::thread_cache`].
+        ///
+        /// [`PoolConfig::thread_cache`]: super::PoolConfig::thread_cache
+        thread_cache: usize,
+
+        /// Configured [`PoolConfig::max_size`].
+        ///
+        /// [`PoolConfig::max_size`]: super::PoolConfig::max_size
+        max_size: usize,
+    },
+
+    /// A [`PoolConfig::timeout`] or [`PoolConfig::leak_threshold`] is
+    /// configured, which requires the [`Pool`] to be built within a tokio
+    /// runtime.
+    ///
+    /// [`Pool`]: super::Pool
+    /// [`PoolConfig::timeout`]: super::PoolConfig::timeout
+    /// [`PoolConfig::leak_threshold`]: super::PoolConfig::leak_threshold
+    NoRuntime,
+
+    /// [`Pool::reconfigure()`] was called with a different
+    /// [`PoolConfig::queue_mode`] than the [`Pool`] has been built with.
+    ///
+    /// [`Pool`]: super::Pool
+    /// [`Pool::reconfigure()`]: super::Pool::reconfigure
+    /// [`PoolConfig::queue_mode`]: super::PoolConfig::queue_mode
+    QueueModeChanged,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroMaxSize => write!(f, "`max_size` must be greater than zero"),
+            Self::ZeroLeakThreshold => write!(f, "`leak_threshold` must be greater than zero"),
+            Self::ZeroShards => write!(f, "Sharded `queue_mode` must have at least one shard"),
+            Self::ThreadCacheExceedsMaxSize {
+                thread_cache,
+                max_size,
+            } => write!(
+                f,
+                "`thread_cache` of {thread_cache} exceeds `max_size` of {max_size}"
+            ),
+            Self::NoRuntime => write!(
+                f,
+                "Timeouts and leak detection require the pool to be built within a tokio runtime"
+            ),
+            Self::QueueModeChanged => write!(f, "`queue_mode` of a running pool can't be changed"),
+        }
+    }
+}
+
+impl Error for BuildError {}
@@ -0,0 +1,181 @@
+use std::{fmt, future::Future, pin::Pin};
+
+use crate::{NativeManager, ObjectMetrics};
+
+/// The result returned by hooks
+pub type HookResult<E> = Result<(), HookError<E>>;
+
+/// The boxed future that should be returned by async hooks
+pub type HookFuture<'a, E> = Pin<Box<dyn Future<Output = HookResult<E>> + Send + 'a>>;
+
+/// Function signature for sync callbacks
+type SyncFn<M> = dyn Fn(&mut <M as NativeManager>::Type, &ObjectMetrics) -> HookResult<<M as NativeManager>::Error>
+    + Sync
+    + Send;
+
+/// Function signature for async callbacks
+type AsyncFn<M> = dyn for<'a> Fn(
+        &'a mut <M as NativeManager>::Type,
+        &'a ObjectMetrics,
+    ) -> HookFuture<'a, <M as NativeManager>::Error>
+    + Sync
+    + Send;
+
+/// Wrapper for hook functions run around the lifecycle of an [`Object`].
+///
+/// Hooks are registered using [`PoolBuilder::post_create()`],
+/// [`PoolBuilder::pre_recycle()`] and [`PoolBuilder::post_recycle()`].
+///
+/// [`Object`]: crate::Object
+/// [`PoolBuilder::post_create()`]: crate::PoolBuilder::post_create
+/// [`PoolBuilder::pre_recycle()`]: crate::PoolBuilder::pre_recycle
+/// [`PoolBuilder::post_recycle()`]: crate::PoolBuilder::post_recycle
+pub enum Hook<M: NativeManager + ?Sized> {
+    /// Use a plain function (non-async) as a hook
+    Fn(Box<SyncFn<M>>),
+    /// Use an async function as a hook
+    AsyncFn(Box<AsyncFn<M>>),
+}
+
+impl<M: NativeManager + ?Sized> Hook<M> {
+    /// Create Hook from sync function
+    pub fn sync_fn(
+        f: impl Fn(&mut M::Type, &ObjectMetrics) -> HookResult<M::Error> + Sync + Send + 'static,
+    ) -> Self {
+        Self::Fn(Box::new(f))
+    }
+
+    /// Create Hook from async function
+    pub fn async_fn(
+        f: impl for<'a> Fn(&'a mut M::Type, &'a ObjectMetrics) -> HookFuture<'a, M::Error>
+            + Sync
+            + Send
+            + 'static,
+    ) -> Self {
+        Self::AsyncFn(Box::new(f))
+    }
+
+    async fn apply(&self, obj: &mut M::Type, metrics: &ObjectMetrics) -> HookResult<M::Error> {
+        match self {
+            Self::Fn(f) => f(obj, metrics),
+            Self::AsyncFn(f) => f(obj, metrics).await,
+        }
+    }
+}
+
+impl<M: NativeManager + ?Sized> fmt::Debug for Hook<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fn(_) => f.debug_tuple("Fn").finish(),
+            Self::AsyncFn(_) => f.debug_tuple("AsyncFn").finish(),
+        }
+    }
+}
+
+/// Error which is returned by `post_create`, `pre_recycle` and
+/// `post_recycle` hooks.
+#[derive(Debug)]
+pub enum HookError<E> {
+    /// Hook failed for some other reason.
+    Message(String),
+
+    /// Hook failed for some other reason, described by a static message.
+    StaticMessage(&'static str),
+
+    /// Error caused by the backend.
+    Backend(E),
+}
+
+impl<E> HookError<E> {
+    /// Converts the backend error using `f`, leaving messages as they are.
+    pub fn map_backend<F>(self, f: impl FnOnce(E) -> F) -> HookError<F> {
+        match self {
+            Self::Message(msg) => HookError::Message(msg),
+            Self::StaticMessage(msg) => HookError::StaticMessage(msg),
+            Self::Backend(e) => HookError::Backend(f(e)),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for HookError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Message(msg) => write!(f, "{msg}"),
+            Self::StaticMessage(msg) => write!(f, "{msg}"),
+            Self::Backend(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for HookError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Message(_) | Self::StaticMessage(_) => None,
+            Self::Backend(e) => Some(e),
+        }
+    }
+}
+
+/// The hooks registered for the three stages of an [`Object`]'s lifecycle.
+///
+/// [`Object`]: crate::Object
+pub(crate) struct Hooks<M: NativeManager + ?Sized> {
+    pub(crate) post_create: HookVec<M>,
+    pub(crate) pre_recycle: HookVec<M>,
+    pub(crate) post_recycle: HookVec<M>,
+}
+
+impl<M: NativeManager + ?Sized> Default for Hooks<M> {
+    fn default() -> Self {
+        Self {
+            post_create: HookVec::default(),
+            pre_recycle: HookVec::default(),
+            post_recycle: HookVec::default(),
+        }
+    }
+}
+
+impl<M: NativeManager + ?Sized> fmt::Debug for Hooks<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("post_create", &self.post_create)
+            .field("pre_recycle", &self.pre_recycle)
+            .field("post_recycle", &self.post_recycle)
+            .finish()
+    }
+}
+
+pub(crate) struct HookVec<M: NativeManager + ?Sized> {
+    vec: Vec<Hook<M>>,
+}
+
+impl<M: NativeManager + ?Sized> Default for HookVec<M> {
+    fn default() -> Self {
+        Self { vec: Vec::new() }
+    }
+}
+
+impl<M: NativeManager + ?Sized> fmt::Debug for HookVec<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.vec).finish()
+    }
+}
+
+impl<M: NativeManager + ?Sized> HookVec<M> {
+    /// Runs all hooks in the order they were registered, stopping at the
+    /// first one that fails.
+    pub(crate) async fn apply(
+        &self,
+        obj: &mut M::Type,
+        metrics: &ObjectMetrics,
+    ) -> HookResult<M::Error> {
+        for hook in &self.vec {
+            hook.apply(obj, metrics).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn push(&mut self, hook: Hook<M>) {
+        self.vec.push(hook);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    backtrace::Backtrace,
+    fmt,
+    panic::Location,
+    sync::{atomic::Ordering, Arc, Weak},
+    time::Duration,
+};
+
+use tokio::task::JoinHandle;
+
+use crate::PoolMetrics;
+
+/// Report about an [`Object`] which has been held for longer than the
+/// configured [`PoolConfig::leak_threshold`].
+///
+/// [`Object`]: crate::Object
+/// [`PoolConfig::leak_threshold`]: crate::PoolConfig::leak_threshold
+#[derive(Debug)]
+pub struct LeakReport {
+    /// Location of the [`Pool::get()`] call which checked out the object.
+    ///
+    /// [`Pool::get()`]: crate::Pool::get
+    pub location: &'static Location<'static>,
+
+    /// Time the object had been held for when it was reported.
+    pub held_for: Duration,
+
+    /// Backtrace of the [`Pool::get()`] call which checked out the object.
+    ///
+    /// This is only captured if backtraces are enabled via the
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment variables. See
+    /// [`Backtrace::capture()`] for details.
+    ///
+    /// [`Pool::get()`]: crate::Pool::get
+    pub backtrace: Backtrace,
+}
+
+/// Callback invoked with a [`LeakReport`] for every leaked object.
+pub(crate) struct LeakCallback(Box<dyn Fn(&LeakReport) + Sync + Send>);
+
+impl LeakCallback {
+    pub(crate) fn new(f: impl Fn(&LeakReport) + Sync + Send + 'static) -> Self {
+        Self(Box::new(f))
+    }
+}
+
+impl fmt::Debug for LeakCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LeakCallback").finish()
+    }
+}
+
+/// Site of a [`Pool::get()`] call, captured only if leak detection is enabled.
+///
+/// [`Pool::get()`]: crate::Pool::get
+#[derive(Debug)]
+pub(crate) struct Checkout {
+    location: &'static Location<'static>,
+    backtrace: Backtrace,
+}
+
+impl Checkout {
+    #[track_caller]
+    pub(crate) fn capture(leak_threshold: Option<Duration>) -> Option<Self> {
+        let location = Location::caller();
+        leak_threshold.map(|_| Self {
+            location,
+            backtrace: Backtrace::capture(),
+        })
+    }
+
+    /// Spawns a task reporting the checked out object as leaked unless it is
+    /// aborted before `threshold` has passed.
+    pub(crate) fn watch(
+        self,
+        threshold: Duration,
+        metrics: Weak<PoolMetrics>,
+        callback: Option<Arc<LeakCallback>>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            tokio::time::sleep(threshold).await;
+            if let Some(metrics) = metrics.upgrade() {
+                let _ = metrics.leak_count.fetch_add(1, Ordering::Relaxed);
+                if let Some(callback) = callback {
+                    (callback.0)(&LeakReport {
+                        location: self.location,
+                        held_for: threshold,
+                        backtrace: self.backtrace,
+                    });
+                }
+            }
+        })
+    }
+}
@@ -28,36 +28,50 @@
 mod array_queue;
 mod builder;
 mod config;
+mod duration;
 mod errors;
+mod hooks;
+mod leak;
+mod manager_ext;
 mod metrics;
 mod object;
 mod pool;
+mod status;
+mod sync;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+mod thread_cache;
 
 pub use self::{
     builder::PoolBuilder,
-    config::PoolConfig,
-    errors::{PoolError, TimeoutType},
-    metrics::PoolMetrics,
+    config::{FromEnvError, PoolConfig, QueueMode},
+    errors::{BuildError, PoolError, TimeoutType},
+    hooks::{Hook, HookError, HookFuture, HookResult},
+    leak::LeakReport,
+    manager_ext::{
+        AndThenCreate, CreateTimeoutError, ManagerExt, MapErr, ValidateWith, WithCreateTimeout,
+        WithRecycleTimeout,
+    },
+    metrics::{ObjectMetrics, PoolMetrics},
     object::Object,
-    pool::Pool,
+    pool::{Pool, WithFuture},
+    status::{HighWaterMarks, Status, StatusWatch},
+};
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    sync::{
+        atomic::{self, AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
 };
 
 use array_queue::ArrayQueue;
 use async_trait::async_trait;
-use tokio::sync::Semaphore;
-
-/// The current pool status.
-#[derive(Clone, Copy, Debug)]
-pub struct Status {
-    /// The maximum size of the pool.
-    pub max_size: usize,
-
-    /// The current items idle in the pool.
-    pub size: usize,
-
-    /// The permits available from the pool.
-    pub available: usize,
-}
+use thread_cache::ThreadCache;
+use tokio::sync::{oneshot, Semaphore};
 
 /// Manager responsible for creating new [`Object`]s or recycling existing ones.
 #[async_trait]
@@ -77,19 +91,433 @@ pub trait Manager: Sync + Send {
     ///
     /// Returns [`None`] if the instance couldn't be recycled.
     async fn recycle(&self, obj: Self::Type) -> Option<Self::Type>;
+
+    /// Tells whether the given error is transient, e.g. a refused connection
+    /// while the backend restarts, so retrying later might succeed.
+    ///
+    /// Errors are considered permanent by default. See
+    /// [`PoolError::is_retryable()`].
+    fn is_transient(&self, _error: &Self::Error) -> bool {
+        false
+    }
+}
+
+/// Manager responsible for creating new [`Object`]s or recycling existing ones
+/// without boxing the returned futures.
+///
+/// This is the trait a [`Pool`] is actually built upon. Every [`Manager`]
+/// implements it automatically, but implementing it directly using plain
+/// `async fn`s avoids the heap allocation [`async_trait`] performs on every
+/// call to [`Manager::create()`] and [`Manager::recycle()`]:
+///
+/// ```rust
+/// use std::convert::Infallible;
+///
+/// struct Manager;
+///
+/// impl deadpool::NativeManager for Manager {
+///     type Type = usize;
+///     type Error = Infallible;
+///
+///     async fn create(&self) -> Result<usize, Infallible> {
+///         Ok(0)
+///     }
+///
+///     async fn recycle(&self, obj: usize) -> Option<usize> {
+///         Some(obj)
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let pool = deadpool::Pool::builder(Manager).build().unwrap();
+/// assert_eq!(*pool.get().await.unwrap(), 0);
+/// # }
+/// ```
+///
+/// [`async_trait`]: macro@async_trait
+pub trait NativeManager: Sync + Send {
+    /// Type of [`Object`]s that this [`NativeManager`] creates and recycles.
+    type Type;
+    /// Error that this [`NativeManager`] can return when creating and/or
+    /// recycling [`Object`]s.
+    type Error;
+
+    /// Creates a new instance of [`NativeManager::Type`].
+    fn create(&self) -> impl Future<Output = Result<Self::Type, Self::Error>> + Send;
+
+    /// Tries to recycle an instance of [`NativeManager::Type`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`None`] if the instance couldn't be recycled.
+    fn recycle(&self, obj: Self::Type) -> impl Future<Output = Option<Self::Type>> + Send;
+
+    /// Tells whether the given error is transient, e.g. a refused connection
+    /// while the backend restarts, so retrying later might succeed.
+    ///
+    /// Errors are considered permanent by default. See
+    /// [`PoolError::is_retryable()`].
+    fn is_transient(&self, _error: &Self::Error) -> bool {
+        false
+    }
+}
+
+impl<M: Manager> NativeManager for M {
+    type Type = M::Type;
+    type Error = M::Error;
+
+    fn create(&self) -> impl Future<Output = Result<Self::Type, Self::Error>> + Send {
+        Manager::create(self)
+    }
+
+    fn recycle(&self, obj: Self::Type) -> impl Future<Output = Option<Self::Type>> + Send {
+        Manager::recycle(self, obj)
+    }
+
+    fn is_transient(&self, error: &Self::Error) -> bool {
+        Manager::is_transient(self, error)
+    }
 }
 
 #[derive(Debug)]
 struct Slots<T> {
-    vec: ArrayQueue<T>,
+    /// Idle objects, see [`QueueMode`].
+    shards: Box<[ArrayQueue<T>]>,
+
+    /// Idle objects which could not be pushed into `shards` right away because
+    /// the next slot was still in use by another thread, or because the pool
+    /// has been grown beyond the capacity of the `shards`.
+    overflow: Mutex<Vec<T>>,
+
+    /// Tasks waiting for objects.
+    waiters: Mutex<Waiters<T>>,
+
+    /// Number of entries in `waiters`, so returning an object doesn't need to
+    /// lock it while nobody is waiting.
+    waiting: AtomicUsize,
+
+    /// Idle objects which kept their permit, see [`PoolConfig::thread_cache`].
+    cache: ThreadCache<T>,
+
     semaphore: Semaphore,
+
+    /// Maximum number of objects, see [`PoolConfig::max_size`].
+    max_size: AtomicUsize,
+
+    /// Permits still to be removed from the `semaphore` after the pool has
+    /// been shrunk while they were in use.
+    shrink: Mutex<usize>,
+
+    /// Whether `shrink` is non-zero, so releasing permits doesn't need to
+    /// lock it otherwise.
+    shrinking: AtomicBool,
 }
 
 impl<T> Slots<T> {
-    pub(crate) fn new(max_size: usize) -> Self {
+    pub(crate) fn new(config: &PoolConfig) -> Self {
+        let max_size = config.max_size;
         Self {
-            vec: ArrayQueue::new(max_size),
+            shards: (0..config.queue_mode.shards())
+                .map(|_| ArrayQueue::new(max_size))
+                .collect(),
+            overflow: Mutex::new(Vec::new()),
+            waiters: Mutex::new(Waiters::default()),
+            waiting: AtomicUsize::new(0),
+            cache: ThreadCache::new(config.thread_cache),
             semaphore: Semaphore::new(max_size),
+            max_size: AtomicUsize::new(max_size),
+            shrink: Mutex::new(0),
+            shrinking: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the index of the shard belonging to the current thread.
+    fn shard(&self) -> usize {
+        match self.shards.len() {
+            1 => 0,
+            len => thread_index() % len,
+        }
+    }
+
+    /// Stores an idle object without ever spinning, so it's safe to be called
+    /// from [`Drop`] on an async worker thread.
+    pub(crate) fn push(&self, value: T) {
+        if let Err(err) = self.shards[self.shard()].push(value) {
+            self.overflow.lock().unwrap().push(err.into_inner());
+        }
+    }
+
+    /// Takes an idle object, preferably from the shard of the current thread
+    /// before stealing one from the other shards.
+    pub(crate) async fn pop(&self) -> Option<T> {
+        let start = self.shard();
+        let len = self.shards.len();
+        for i in 0..len {
+            if let Some(value) = self.shards[(start + i) % len].pop().await {
+                return Some(value);
+            }
+        }
+        self.overflow.lock().unwrap().pop()
+    }
+
+    /// Returns the number of idle objects, including the cached ones.
+    pub(crate) fn len(&self) -> usize {
+        let len: usize = self.shards.iter().map(ArrayQueue::len).sum();
+        len + self.overflow.lock().unwrap().len() + self.cache.len()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.max_size.load(Ordering::Relaxed)
+    }
+
+    /// Changes the maximum number of objects by adding or removing permits.
+    ///
+    /// Permits which are currently in use are removed as soon as they are
+    /// released. Idle objects exceeding the new size have to be dropped by
+    /// the caller.
+    pub(crate) fn resize(&self, max_size: usize) {
+        let old = self.max_size.swap(max_size, Ordering::Relaxed);
+        if max_size > old {
+            self.add_permits(max_size - old);
+        } else if max_size < old {
+            let mut shrink = self.shrink.lock().unwrap();
+            *shrink += old - max_size;
+            self.forget_permits(&mut shrink);
+        }
+    }
+
+    /// Returns the number of permits available, not counting the ones which
+    /// are about to be removed after the pool has been shrunk.
+    pub(crate) fn available_permits(&self) -> usize {
+        let available = self.semaphore.available_permits();
+        if self.shrinking.load(Ordering::Acquire) {
+            available.saturating_sub(*self.shrink.lock().unwrap())
+        } else {
+            available
+        }
+    }
+
+    /// Releases `n` permits, unless they are to be removed after the pool has
+    /// been shrunk.
+    pub(crate) fn add_permits(&self, mut n: usize) {
+        if self.shrinking.load(Ordering::Acquire) {
+            let mut shrink = self.shrink.lock().unwrap();
+            let removed = n.min(*shrink);
+            *shrink -= removed;
+            n -= removed;
+            // Permits might have been released while the pool was shrunk.
+            self.forget_permits(&mut shrink);
+        }
+        if n > 0 {
+            self.semaphore.add_permits(n);
+        }
+    }
+
+    /// Removes available permits until `shrink` is paid off.
+    fn forget_permits(&self, shrink: &mut usize) {
+        while *shrink > 0 {
+            let Ok(permit) = self.semaphore.try_acquire() else {
+                break;
+            };
+            permit.forget();
+            *shrink -= 1;
+        }
+        self.shrinking.store(*shrink > 0, Ordering::Release);
+    }
+
+    /// Returns an object which is no longer in use.
+    ///
+    /// If no task is waiting, the object is kept in the cache of the current
+    /// thread along with its permit. Otherwise see [`Slots::release()`].
+    pub(crate) fn put(&self, mut value: T) {
+        if !self.semaphore.is_closed() && !self.has_waiters() {
+            match self.cache.try_put(value) {
+                Ok(()) => {
+                    // A task which started waiting in the meantime might
+                    // have missed the cached object. Pairs with the fence
+                    // in `update_waiting()`.
+                    atomic::fence(Ordering::SeqCst);
+                    if self.has_waiters() {
+                        if let Some(value) = self.cache.take() {
+                            self.release(value);
+                        }
+                    }
+                    return;
+                }
+                Err(v) => value = v,
+            }
+        }
+        self.release(value);
+    }
+
+    /// Releases an object along with its permit.
+    ///
+    /// The object is handed directly to the longest waiting task, which takes
+    /// over its permit. Only if no task is waiting, it's stored as idle object
+    /// and its permit is released.
+    fn release(&self, mut value: T) {
+        while self.has_waiters() {
+            let waiter = {
+                let mut waiters = self.waiters.lock().unwrap();
+                let waiter = waiters.pop();
+                self.update_waiting(&waiters);
+                waiter
+            };
+            let Some(waiter) = waiter else {
+                break;
+            };
+            match waiter.send(value) {
+                Ok(()) => return,
+                // The waiting task has gone away in the meantime.
+                Err(v) => value = v,
+            }
+        }
+        self.push(value);
+        self.add_permits(1);
+    }
+
+    fn has_waiters(&self) -> bool {
+        self.waiting.load(Ordering::Relaxed) > 0
+    }
+
+    /// Publishes the number of entries in `waiters` after it has been
+    /// changed while holding its lock.
+    fn update_waiting(&self, waiters: &Waiters<T>) {
+        self.waiting
+            .store(waiters.queue.len() + waiters.batches, Ordering::Relaxed);
+        // A newly registered task looks for cached objects afterwards, while
+        // `put()` checks for waiters after caching an object, so at least one
+        // of them sees the other.
+        atomic::fence(Ordering::SeqCst);
+    }
+
+    /// Takes an object which kept its permit from the cache of the current
+    /// thread.
+    pub(crate) fn take_cached(&self) -> Option<T> {
+        if self.semaphore.is_closed() {
+            return None;
+        }
+        self.cache.try_take()
+    }
+
+    /// Changes the number of objects cached per thread, see
+    /// [`PoolConfig::thread_cache`].
+    pub(crate) fn set_cache_capacity(&self, capacity: usize) {
+        self.cache.set_capacity(capacity);
+    }
+
+    /// Takes an object which kept its permit from the cache of any thread.
+    pub(crate) fn steal_cached(&self) -> Option<T> {
+        self.cache.steal()
+    }
+
+    /// Returns the number of cached objects which kept their permit.
+    pub(crate) fn cached(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Moves all cached objects back into the shared queue, releasing their
+    /// permits.
+    pub(crate) fn flush_cache(&self) {
+        for value in self.cache.drain() {
+            self.release(value);
+        }
+    }
+
+    /// Registers a new waiting task to which the next object passed to
+    /// [`Slots::put()`] may be handed.
+    pub(crate) fn wait(&self) -> oneshot::Receiver<T> {
+        let (tx, rx) = oneshot::channel();
+        let mut waiters = self.waiters.lock().unwrap();
+        // Tasks which stopped waiting are skipped by `release()`. Pruning
+        // them here only keeps the queue from growing while no objects are
+        // returned, so it's done whenever the queue has doubled in length.
+        if waiters.queue.len() >= 2 * waiters.pruned_len {
+            waiters.queue.retain(|tx| !tx.is_closed());
+            waiters.pruned_len = waiters.queue.len().max(1);
+        }
+        waiters.queue.push_back(tx);
+        self.update_waiting(&waiters);
+        rx
+    }
+
+    /// Registers a task waiting for multiple permits at once until the
+    /// returned guard is dropped.
+    ///
+    /// Meanwhile objects are neither cached nor handed over directly, so their
+    /// permits are released to the semaphore in order to satisfy the batch.
+    pub(crate) fn wait_batch(&self) -> BatchGuard<'_, T> {
+        {
+            let mut waiters = self.waiters.lock().unwrap();
+            waiters.batches += 1;
+            self.update_waiting(&waiters);
+        }
+        self.flush_cache();
+        BatchGuard { slots: self }
+    }
+
+    /// Wakes up all waiting tasks without handing them an object.
+    pub(crate) fn clear_waiters(&self) {
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.queue.clear();
+        self.update_waiting(&waiters);
+    }
+}
+
+/// Tasks waiting for objects.
+#[derive(Debug)]
+struct Waiters<T> {
+    /// Tasks waiting for a single object, longest waiting first.
+    queue: VecDeque<oneshot::Sender<T>>,
+
+    /// Number of tasks waiting for multiple permits at once.
+    batches: usize,
+
+    /// Length of `queue` after it has last been pruned, see [`Slots::wait()`].
+    pruned_len: usize,
+}
+
+impl<T> Default for Waiters<T> {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            batches: 0,
+            pruned_len: 1,
+        }
+    }
+}
+
+impl<T> Waiters<T> {
+    /// Returns the task an object should be handed to, if any.
+    fn pop(&mut self) -> Option<oneshot::Sender<T>> {
+        if self.batches > 0 {
+            return None;
         }
+        let tx = self.queue.pop_front()?;
+        self.pruned_len = self.pruned_len.min(self.queue.len()).max(1);
+        Some(tx)
+    }
+}
+
+/// Guard returned by [`Slots::wait_batch()`].
+pub(crate) struct BatchGuard<'a, T> {
+    slots: &'a Slots<T>,
+}
+
+impl<T> Drop for BatchGuard<'_, T> {
+    fn drop(&mut self) {
+        let mut waiters = self.slots.waiters.lock().unwrap();
+        waiters.batches -= 1;
+        self.slots.update_waiting(&waiters);
+    }
+}
+
+/// Returns a distinct index for the current thread.
+fn thread_index() -> usize {
+    static NEXT_INDEX: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static INDEX: usize = NEXT_INDEX.fetch_add(1, Ordering::Relaxed);
     }
+    INDEX.with(|index| *index)
 }
@@ -0,0 +1,292 @@
+use std::{fmt, future::Future, time::Duration};
+
+use crate::NativeManager;
+
+/// Extension methods for layering common behaviour on top of any
+/// [`NativeManager`] (and therefore any [`Manager`]).
+///
+/// Every method consumes the manager and returns a new [`NativeManager`]
+/// wrapping it, so the combinators can be chained:
+///
+/// ```rust
+/// # use std::{convert::Infallible, time::Duration};
+/// # use async_trait::async_trait;
+/// use deadpool::{ManagerExt, Pool};
+/// # struct Manager;
+/// # #[async_trait]
+/// # impl deadpool::Manager for Manager {
+/// #     type Type = usize;
+/// #     type Error = Infallible;
+/// #     async fn create(&self) -> Result<usize, Infallible> { Ok(0) }
+/// #     async fn recycle(&self, obj: usize) -> Option<usize> { Some(obj) }
+/// # }
+///
+/// let manager = Manager
+///     .and_then_create(|obj| async move { Ok(obj + 1) })
+///     .validate_with(|obj| async move { Some(obj) })
+///     .with_create_timeout(Duration::from_secs(5));
+/// let pool = Pool::builder(manager).build().unwrap();
+/// ```
+///
+/// [`Manager`]: crate::Manager
+pub trait ManagerExt: NativeManager + Sized {
+    /// Fails [`NativeManager::create()`] with [`CreateTimeoutError::Timeout`]
+    /// if it doesn't complete within the given `timeout`.
+    fn with_create_timeout(self, timeout: Duration) -> WithCreateTimeout<Self> {
+        WithCreateTimeout {
+            manager: self,
+            timeout,
+        }
+    }
+
+    /// Discards objects whose [`NativeManager::recycle()`] doesn't complete within
+    /// the given `timeout`.
+    fn with_recycle_timeout(self, timeout: Duration) -> WithRecycleTimeout<Self> {
+        WithRecycleTimeout {
+            manager: self,
+            timeout,
+        }
+    }
+
+    /// Runs `f` on every newly created object, e.g. to issue setup statements.
+    ///
+    /// If `f` fails, the object is dropped and its error is returned from
+    /// [`NativeManager::create()`].
+    fn and_then_create<F, Fut>(self, f: F) -> AndThenCreate<Self, F>
+    where
+        F: Fn(Self::Type) -> Fut + Send + Sync,
+        Fut: Future<Output = Result<Self::Type, Self::Error>> + Send,
+    {
+        AndThenCreate { manager: self, f }
+    }
+
+    /// Runs `f` on every successfully recycled object before it is handed
+    /// out again, e.g. to send a validation ping.
+    ///
+    /// Returning [`None`] from `f` discards the object, just like
+    /// [`NativeManager::recycle()`] does.
+    fn validate_with<F, Fut>(self, f: F) -> ValidateWith<Self, F>
+    where
+        F: Fn(Self::Type) -> Fut + Send + Sync,
+        Fut: Future<Output = Option<Self::Type>> + Send,
+    {
+        ValidateWith { manager: self, f }
+    }
+
+    /// Converts errors returned by [`NativeManager::create()`] using `f`.
+    fn map_err<F, E>(self, f: F) -> MapErr<Self, F>
+    where
+        F: Fn(Self::Error) -> E + Send + Sync,
+    {
+        MapErr { manager: self, f }
+    }
+}
+
+impl<M: NativeManager> ManagerExt for M {}
+
+/// [`NativeManager`] returned by [`ManagerExt::with_create_timeout()`].
+#[derive(Clone, Debug)]
+pub struct WithCreateTimeout<M> {
+    manager: M,
+    timeout: Duration,
+}
+
+impl<M> NativeManager for WithCreateTimeout<M>
+where
+    M: NativeManager,
+{
+    type Type = M::Type;
+    type Error = CreateTimeoutError<M::Error>;
+
+    async fn create(&self) -> Result<Self::Type, Self::Error> {
+        tokio::time::timeout(self.timeout, self.manager.create())
+            .await
+            .map_err(|_| CreateTimeoutError::Timeout(self.timeout))?
+            .map_err(CreateTimeoutError::Backend)
+    }
+
+    fn recycle(&self, obj: Self::Type) -> impl Future<Output = Option<Self::Type>> + Send {
+        self.manager.recycle(obj)
+    }
+
+    fn is_transient(&self, error: &Self::Error) -> bool {
+        match error {
+            CreateTimeoutError::Timeout(_) => true,
+            CreateTimeoutError::Backend(e) => self.manager.is_transient(e),
+        }
+    }
+}
+
+/// Error of a [`NativeManager`] returned by [`ManagerExt::with_create_timeout()`].
+#[derive(Debug)]
+pub enum CreateTimeoutError<E> {
+    /// Creating the object took longer than the contained [`Duration`].
+    Timeout(Duration),
+
+    /// The wrapped [`NativeManager`] reported an error.
+    Backend(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CreateTimeoutError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout(d) => write!(f, "Creating a new object took longer than {d:?}"),
+            Self::Backend(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for CreateTimeoutError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Timeout(_) => None,
+            Self::Backend(e) => Some(e),
+        }
+    }
+}
+
+/// [`NativeManager`] returned by [`ManagerExt::with_recycle_timeout()`].
+#[derive(Clone, Debug)]
+pub struct WithRecycleTimeout<M> {
+    manager: M,
+    timeout: Duration,
+}
+
+impl<M> NativeManager for WithRecycleTimeout<M>
+where
+    M: NativeManager,
+{
+    type Type = M::Type;
+    type Error = M::Error;
+
+    fn create(&self) -> impl Future<Output = Result<Self::Type, Self::Error>> + Send {
+        self.manager.create()
+    }
+
+    fn recycle(&self, obj: Self::Type) -> impl Future<Output = Option<Self::Type>> + Send {
+        let recycle = self.manager.recycle(obj);
+        async move {
+            tokio::time::timeout(self.timeout, recycle)
+                .await
+                .ok()
+                .flatten()
+        }
+    }
+
+    fn is_transient(&self, error: &Self::Error) -> bool {
+        self.manager.is_transient(error)
+    }
+}
+
+/// [`NativeManager`] returned by [`ManagerExt::and_then_create()`].
+#[derive(Clone)]
+pub struct AndThenCreate<M, F> {
+    manager: M,
+    f: F,
+}
+
+impl<M: fmt::Debug, F> fmt::Debug for AndThenCreate<M, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AndThenCreate")
+            .field("manager", &self.manager)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M, F, Fut> NativeManager for AndThenCreate<M, F>
+where
+    M: NativeManager,
+    M::Type: Send,
+    F: Fn(M::Type) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<M::Type, M::Error>> + Send,
+{
+    type Type = M::Type;
+    type Error = M::Error;
+
+    async fn create(&self) -> Result<Self::Type, Self::Error> {
+        let obj = self.manager.create().await?;
+        (self.f)(obj).await
+    }
+
+    fn recycle(&self, obj: Self::Type) -> impl Future<Output = Option<Self::Type>> + Send {
+        self.manager.recycle(obj)
+    }
+
+    fn is_transient(&self, error: &Self::Error) -> bool {
+        self.manager.is_transient(error)
+    }
+}
+
+/// [`NativeManager`] returned by [`ManagerExt::validate_with()`].
+#[derive(Clone)]
+pub struct ValidateWith<M, F> {
+    manager: M,
+    f: F,
+}
+
+impl<M: fmt::Debug, F> fmt::Debug for ValidateWith<M, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidateWith")
+            .field("manager", &self.manager)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M, F, Fut> NativeManager for ValidateWith<M, F>
+where
+    M: NativeManager,
+    M::Type: Send,
+    F: Fn(M::Type) -> Fut + Send + Sync,
+    Fut: Future<Output = Option<M::Type>> + Send,
+{
+    type Type = M::Type;
+    type Error = M::Error;
+
+    fn create(&self) -> impl Future<Output = Result<Self::Type, Self::Error>> + Send {
+        self.manager.create()
+    }
+
+    async fn recycle(&self, obj: Self::Type) -> Option<Self::Type> {
+        let obj = self.manager.recycle(obj).await?;
+        (self.f)(obj).await
+    }
+
+    fn is_transient(&self, error: &Self::Error) -> bool {
+        self.manager.is_transient(error)
+    }
+}
+
+/// [`NativeManager`] returned by [`ManagerExt::map_err()`].
+///
+/// As the original errors are gone once mapped, all errors are considered
+/// permanent by [`NativeManager::is_transient()`].
+#[derive(Clone)]
+pub struct MapErr<M, F> {
+    manager: M,
+    f: F,
+}
+
+impl<M: fmt::Debug, F> fmt::Debug for MapErr<M, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapErr")
+            .field("manager", &self.manager)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M, F, E> NativeManager for MapErr<M, F>
+where
+    M: NativeManager,
+    F: Fn(M::Error) -> E + Send + Sync,
+{
+    type Type = M::Type;
+    type Error = E;
+
+    async fn create(&self) -> Result<Self::Type, Self::Error> {
+        self.manager.create().await.map_err(&self.f)
+    }
+
+    fn recycle(&self, obj: Self::Type) -> impl Future<Output = Option<Self::Type>> + Send {
+        self.manager.recycle(obj)
+    }
+}
@@ -1,4 +1,7 @@
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
 
 use tokio::time::Instant;
 
@@ -12,6 +15,34 @@ pub struct PoolMetrics {
     pub total_waiting: AtomicU64,
     /// The number of times an object request failed
     pub failure_count: AtomicUsize,
+    /// The number of objects which were held for longer than the configured
+    /// [`PoolConfig::leak_threshold`]
+    ///
+    /// [`PoolConfig::leak_threshold`]: crate::PoolConfig::leak_threshold
+    pub leak_count: AtomicUsize,
+    /// The number of objects which were discarded using [`Object::discard()`]
+    /// or [`Object::mark_broken()`] instead of being returned to the pool
+    ///
+    /// [`Object::discard()`]: crate::Object::discard
+    /// [`Object::mark_broken()`]: crate::Object::mark_broken
+    pub discard_count: AtomicUsize,
+    /// The number of times [`NativeManager::create()`] failed
+    ///
+    /// [`NativeManager::create()`]: crate::NativeManager::create
+    pub create_failure_count: AtomicUsize,
+    /// The number of times a `post_create` hook failed
+    pub post_create_failure_count: AtomicUsize,
+    /// The number of objects which were dropped because a `pre_recycle` hook
+    /// failed
+    pub pre_recycle_failure_count: AtomicUsize,
+    /// The number of objects which were dropped because
+    /// [`NativeManager::recycle()`] failed
+    ///
+    /// [`NativeManager::recycle()`]: crate::NativeManager::recycle
+    pub recycle_failure_count: AtomicUsize,
+    /// The number of objects which were dropped because a `post_recycle` hook
+    /// failed
+    pub post_recycle_failure_count: AtomicUsize,
 }
 
 impl Default for PoolMetrics {
@@ -20,6 +51,13 @@ impl Default for PoolMetrics {
             total_active: AtomicU64::new(0),
             total_waiting: AtomicU64::new(0),
             failure_count: AtomicUsize::new(0),
+            leak_count: AtomicUsize::new(0),
+            discard_count: AtomicUsize::new(0),
+            create_failure_count: AtomicUsize::new(0),
+            post_create_failure_count: AtomicUsize::new(0),
+            pre_recycle_failure_count: AtomicUsize::new(0),
+            recycle_failure_count: AtomicUsize::new(0),
+            post_recycle_failure_count: AtomicUsize::new(0),
         }
     }
 }
@@ -51,4 +89,72 @@ impl PoolMetrics {
     pub fn failure_count(&self) -> usize {
         self.failure_count.load(Ordering::Relaxed)
     }
+    /// Get the total number of objects which were reported as leaked
+    pub fn leak_count(&self) -> usize {
+        self.leak_count.load(Ordering::Relaxed)
+    }
+    /// Get the total number of objects which were discarded
+    pub fn discard_count(&self) -> usize {
+        self.discard_count.load(Ordering::Relaxed)
+    }
+    /// Get the total number of failures to create a new object
+    pub fn create_failure_count(&self) -> usize {
+        self.create_failure_count.load(Ordering::Relaxed)
+    }
+    /// Get the total number of failures of `post_create` hooks
+    pub fn post_create_failure_count(&self) -> usize {
+        self.post_create_failure_count.load(Ordering::Relaxed)
+    }
+    /// Get the total number of failures of `pre_recycle` hooks
+    pub fn pre_recycle_failure_count(&self) -> usize {
+        self.pre_recycle_failure_count.load(Ordering::Relaxed)
+    }
+    /// Get the total number of failures to recycle an object
+    pub fn recycle_failure_count(&self) -> usize {
+        self.recycle_failure_count.load(Ordering::Relaxed)
+    }
+    /// Get the total number of failures of `post_recycle` hooks
+    pub fn post_recycle_failure_count(&self) -> usize {
+        self.post_recycle_failure_count.load(Ordering::Relaxed)
+    }
+}
+
+/// Statistics regarding an object returned by the pool
+#[derive(Clone, Copy, Debug)]
+#[must_use]
+pub struct ObjectMetrics {
+    /// The instant when this object was created
+    pub created: Instant,
+    /// The instant when this object was last recycled
+    pub recycled: Option<Instant>,
+    /// The number of times the objects was recycled
+    pub recycle_count: usize,
+    /// The instant when this object was last returned to the pool
+    pub last_used: Option<Instant>,
+}
+
+impl Default for ObjectMetrics {
+    fn default() -> Self {
+        Self {
+            created: Instant::now(),
+            recycled: None,
+            recycle_count: 0,
+            last_used: None,
+        }
+    }
+}
+
+impl ObjectMetrics {
+    /// Access the age of this object
+    #[must_use]
+    pub fn age(&self) -> Duration {
+        self.created.elapsed()
+    }
+
+    /// Get the time elapsed since this object was last returned to the pool,
+    /// or since it was created if it has never been returned.
+    #[must_use]
+    pub fn last_used(&self) -> Duration {
+        self.last_used.unwrap_or(self.created).elapsed()
+    }
 }
@@ -1,12 +1,15 @@
 use std::{
     fmt,
     ops::{Deref, DerefMut},
-    sync::{Arc, Weak},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
 };
 
-use tokio::time::Instant;
+use tokio::{task::JoinHandle, time::Instant};
 
-use crate::{pool::PoolInner, Manager, Pool};
+use crate::{leak::Checkout, metrics::ObjectMetrics, pool::PoolInner, NativeManager, Pool};
 
 /// Wrapper around the actual pooled object which implements [`Deref`],
 /// [`DerefMut`] and [`Drop`] traits.
@@ -14,44 +17,108 @@ use crate::{pool::PoolInner, Manager, Pool};
 /// Use this object just as if it was of type `T` and upon leaving a scope the
 /// [`Drop::drop()`] will take care of returning it to the pool.
 #[must_use]
-pub struct Object<M: Manager + ?Sized> {
+pub struct Object<M: NativeManager + ?Sized> {
     /// The actual object
-    inner: Option<M::Type>,
+    inner: Option<ObjectInner<M::Type>>,
 
     /// Pool to return the pooled object to.
     pool: Weak<PoolInner<M>>,
 
     /// Time this object was claimed
     start: Instant,
+
+    /// Task reporting this object as leaked if it is held for too long.
+    leak_guard: Option<JoinHandle<()>>,
+
+    /// Whether to discard this object instead of returning it to the pool.
+    broken: bool,
 }
 
 impl<M> fmt::Debug for Object<M>
 where
-    M: fmt::Debug + Manager,
+    M: fmt::Debug + NativeManager,
     M::Type: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Object")
             .field("inner", &self.inner)
-            .finish()
+            .finish_non_exhaustive()
     }
 }
 
-// #[derive(Debug)]
-// pub(crate) struct ObjectInner<Type> {
-//     /// Actual pooled object.
-//     pub(crate) obj: Type,
+#[derive(Debug)]
+pub(crate) struct ObjectInner<Type> {
+    /// Actual pooled object.
+    pub(crate) obj: Type,
 
-//     /// Object metrics.
-//     pub(crate) metrics: ObjectMetrics,
-// }
+    /// Object metrics.
+    pub(crate) metrics: ObjectMetrics,
+
+    /// Counts this object towards the size of its pool.
+    pub(crate) size: SizeGuard,
+}
 
-impl<M: Manager> Object<M> {
-    pub(crate) fn new(inner: M::Type, pool: &Arc<PoolInner<M>>) -> Self {
+impl<Type> ObjectInner<Type> {
+    pub(crate) fn new(obj: Type, size: SizeGuard) -> Self {
+        Self {
+            obj,
+            metrics: ObjectMetrics::default(),
+            size,
+        }
+    }
+}
+
+/// Counts an object towards the number of objects existing in a pool as long
+/// as it's alive, including while it's still being created.
+#[derive(Debug)]
+pub(crate) struct SizeGuard(Arc<AtomicUsize>);
+
+impl SizeGuard {
+    /// Counts a new object unless there are `max_size` objects already.
+    pub(crate) fn reserve(size: &Arc<AtomicUsize>, max_size: usize) -> Option<Self> {
+        let mut n = size.load(Ordering::Relaxed);
+        loop {
+            if n >= max_size {
+                return None;
+            }
+            match size.compare_exchange_weak(n, n + 1, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return Some(Self(size.clone())),
+                Err(actual) => n = actual,
+            }
+        }
+    }
+}
+
+impl Drop for SizeGuard {
+    fn drop(&mut self) {
+        let _ = self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl<M: NativeManager> Object<M> {
+    pub(crate) fn new(
+        inner: ObjectInner<M::Type>,
+        pool: &Arc<PoolInner<M>>,
+        checkout: Option<Checkout>,
+    ) -> Self {
+        let leak_guard =
+            pool.config
+                .load()
+                .leak_threshold
+                .zip(checkout)
+                .map(|(threshold, checkout)| {
+                    checkout.watch(
+                        threshold,
+                        Arc::downgrade(&pool.metrics),
+                        pool.leak_callback.clone(),
+                    )
+                });
         Self {
             inner: Some(inner),
             pool: Arc::downgrade(pool),
             start: Instant::now(),
+            leak_guard,
+            broken: false,
         }
     }
 
@@ -59,17 +126,38 @@ impl<M: Manager> Object<M> {
     /// size of the [`Pool`].
     #[must_use]
     pub fn take(mut this: Self) -> M::Type {
-        let inner = this.inner.take().unwrap();
+        let inner = this.inner.take().unwrap().obj;
         if let Some(pool) = Object::pool(&this) {
-            pool.inner.slots.semaphore.add_permits(1);
+            pool.inner.counts.release_in_use();
+            pool.inner.slots.add_permits(1);
+            pool.inner.counts.notify();
         }
         inner
     }
 
-    // /// Get object statistics
-    // pub fn metrics(this: &Self) -> &ObjectMetrics {
-    //     &this.inner.as_ref().unwrap().metrics
-    // }
+    /// Destroys this [`Object`] right away instead of returning it to its
+    /// [`Pool`], e.g. because it's known to be broken.
+    ///
+    /// Unlike [`Object::take()`] the object is dropped. In both cases its slot
+    /// is freed, so the [`Pool`] can create a new object in its place. The
+    /// object is counted in [`PoolMetrics::discard_count`].
+    ///
+    /// [`PoolMetrics::discard_count`]: crate::PoolMetrics::discard_count
+    pub fn discard(mut this: Self) {
+        Self::mark_broken(&mut this);
+    }
+
+    /// Marks this [`Object`] as broken, so it's discarded as if by
+    /// [`Object::discard()`] when dropped instead of being returned to its
+    /// [`Pool`] and recycled.
+    pub fn mark_broken(this: &mut Self) {
+        this.broken = true;
+    }
+
+    /// Get object statistics
+    pub fn metrics(this: &Self) -> &ObjectMetrics {
+        &this.inner.as_ref().unwrap().metrics
+    }
 
     /// Returns the [`Pool`] this [`Object`] belongs to.
     ///
@@ -80,36 +168,43 @@ impl<M: Manager> Object<M> {
     }
 }
 
-impl<M: Manager + ?Sized> Drop for Object<M> {
+impl<M: NativeManager + ?Sized> Drop for Object<M> {
     fn drop(&mut self) {
+        if let Some(leak_guard) = self.leak_guard.take() {
+            leak_guard.abort();
+        }
         if let Some(inner) = self.inner.take() {
             if let Some(pool) = self.pool.upgrade() {
-                pool.return_object(inner, self.start);
+                if self.broken {
+                    pool.discard_object(inner, self.start);
+                } else {
+                    pool.return_object(inner, self.start);
+                }
             }
         }
     }
 }
 
-impl<M: Manager> Deref for Object<M> {
+impl<M: NativeManager> Deref for Object<M> {
     type Target = M::Type;
     fn deref(&self) -> &M::Type {
-        self.inner.as_ref().unwrap()
+        &self.inner.as_ref().unwrap().obj
     }
 }
 
-impl<M: Manager> DerefMut for Object<M> {
+impl<M: NativeManager> DerefMut for Object<M> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.inner.as_mut().unwrap()
+        &mut self.inner.as_mut().unwrap().obj
     }
 }
 
-impl<M: Manager> AsRef<M::Type> for Object<M> {
+impl<M: NativeManager> AsRef<M::Type> for Object<M> {
     fn as_ref(&self) -> &M::Type {
         self
     }
 }
 
-impl<M: Manager> AsMut<M::Type> for Object<M> {
+impl<M: NativeManager> AsMut<M::Type> for Object<M> {
     fn as_mut(&mut self) -> &mut M::Type {
         self
     }
@@ -1,29 +1,45 @@
 use std::{
+    convert::TryFrom,
     fmt,
-    future::Future,
-    sync::{atomic::Ordering, Arc},
+    future::{poll_fn, Future},
+    mem,
+    pin::{pin, Pin},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{ready, Context, Poll},
     time::Duration,
 };
 
-use tokio::{sync::TryAcquireError, time::Instant};
+use crossbeam_utils::atomic::AtomicCell;
+use tokio::{
+    sync::{oneshot, SemaphorePermit, TryAcquireError},
+    time::Instant,
+};
 
 use crate::{
-    metrics::PoolMetrics, Manager, Object, PoolBuilder, PoolConfig, PoolError, Slots, Status,
-    TimeoutType,
+    hooks::Hooks,
+    leak::{Checkout, LeakCallback},
+    metrics::{ObjectMetrics, PoolMetrics},
+    object::{ObjectInner, SizeGuard},
+    status::{Stage, StatusCounts},
+    BuildError, HighWaterMarks, NativeManager, Object, PoolBuilder, PoolConfig, PoolError, Slots,
+    Status, StatusWatch, TimeoutType,
 };
 
 /// Generic object and connection pool.
 ///
 /// This struct can be cloned and transferred across thread boundaries and uses
 /// reference counting for its internal state.
-pub struct Pool<M: Manager> {
+pub struct Pool<M: NativeManager> {
     pub(crate) inner: Arc<PoolInner<M>>,
 }
 
 // Implemented manually to avoid unnecessary trait bound on `W` type parameter.
 impl<M> fmt::Debug for Pool<M>
 where
-    M: fmt::Debug + Manager,
+    M: fmt::Debug + NativeManager,
     M::Type: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -31,7 +47,7 @@ where
     }
 }
 
-impl<M: Manager> Clone for Pool<M> {
+impl<M: NativeManager> Clone for Pool<M> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
@@ -39,7 +55,7 @@ impl<M: Manager> Clone for Pool<M> {
     }
 }
 
-impl<M: Manager> Pool<M> {
+impl<M: NativeManager> Pool<M> {
     /// Get the metrics of the pool
     pub fn metrics(&self) -> &PoolMetrics {
         &self.inner.metrics
@@ -55,9 +71,14 @@ impl<M: Manager> Pool<M> {
     pub(crate) fn from_builder(builder: PoolBuilder<M>) -> Self {
         Self {
             inner: Arc::new(PoolInner {
-                slots: Slots::new(builder.config.max_size),
-                config: builder.config,
-                metrics: PoolMetrics::default(),
+                slots: Slots::new(&builder.config),
+                config: AtomicCell::new(builder.config),
+                reconfigure: Mutex::new(()),
+                counts: StatusCounts::new(),
+                metrics: Arc::new(PoolMetrics::default()),
+                hooks: builder.hooks,
+                leak_callback: builder.leak_callback,
+                size: Arc::new(AtomicUsize::new(0)),
                 manager: builder.manager,
             }),
         }
@@ -69,8 +90,9 @@ impl<M: Manager> Pool<M> {
     /// # Errors
     ///
     /// See [`PoolError`] for details.
-    pub async fn get(&self) -> Result<Object<M>, PoolError<M::Error>> {
-        self.timeout_get(self.inner.config.timeout).await
+    #[track_caller]
+    pub fn get(&self) -> impl Future<Output = Result<Object<M>, PoolError<M::Error>>> + '_ {
+        self.timeout_get(self.inner.config.load().timeout)
     }
 
     /// Retrieves an [`Object`] from this [`Pool`] using a different `timeout`
@@ -79,25 +101,26 @@ impl<M: Manager> Pool<M> {
     /// # Errors
     ///
     /// See [`PoolError`] for details.
-    pub async fn timeout_get(
+    #[track_caller]
+    pub fn timeout_get(
         &self,
         timeouts: Option<Duration>,
-    ) -> Result<Object<M>, PoolError<M::Error>> {
-        let start = Instant::now();
-        let res = self.get_inner(start, timeouts).await;
+    ) -> impl Future<Output = Result<Object<M>, PoolError<M::Error>>> + '_ {
+        let checkout = Checkout::capture(self.inner.config.load().leak_threshold);
+        async move {
+            let start = Instant::now();
+            let res = self.get_inner(start, timeouts, checkout).await;
 
-        self.inner.metrics.record_waiting(start);
+            self.inner.metrics.record_waiting(start);
 
-        match res {
-            Ok(success) => Ok(success),
-            Err(error) => {
+            res.map_err(|error| {
                 let _ = self
                     .inner
                     .metrics
                     .failure_count
                     .fetch_add(1, Ordering::Relaxed);
-                Err(error)
-            }
+                error
+            })
         }
     }
 
@@ -105,6 +128,7 @@ impl<M: Manager> Pool<M> {
         &self,
         now: Instant,
         timeouts: Option<Duration>,
+        checkout: Option<Checkout>,
     ) -> Result<Object<M>, PoolError<M::Error>> {
         let non_blocking = match timeouts {
             Some(t) => t.is_zero(),
@@ -112,55 +136,427 @@ impl<M: Manager> Pool<M> {
         };
         let instant = timeouts.and_then(|d| now.checked_add(d));
 
-        let permit = if non_blocking {
-            self.inner
-                .slots
-                .semaphore
-                .try_acquire()
-                .map_err(|e| match e {
-                    TryAcquireError::Closed => PoolError::Closed,
-                    TryAcquireError::NoPermits => PoolError::Timeout(TimeoutType::Wait),
-                })?
-        } else {
-            apply_timeout(TimeoutType::Wait, instant, async {
-                self.inner
-                    .slots
-                    .semaphore
-                    .acquire()
-                    .await
-                    .map_err(|_| PoolError::Closed)
-            })
-            .await?
-        };
+        let slots = &self.inner.slots;
+        let cached = slots.take_cached().map(|inner_obj| {
+            let slot = SlotGuard { slots };
+            (slot, Some(inner_obj))
+        });
+        self.get_object(cached, non_blocking, instant, checkout)
+            .await
+    }
 
+    /// Fills the given slot or a newly acquired one with an [`Object`],
+    /// acquiring another slot whenever it had to be given up.
+    async fn get_object(
+        &self,
+        mut acquired: Option<Acquired<'_, M::Type>>,
+        non_blocking: bool,
+        instant: Option<Instant>,
+        mut checkout: Option<Checkout>,
+    ) -> Result<Object<M>, PoolError<M::Error>> {
+        loop {
+            let (slot, handoff) = match acquired.take() {
+                Some(acquired) => acquired,
+                None if non_blocking => self
+                    .try_acquire_slot()?
+                    .ok_or(PoolError::Timeout(TimeoutType::Wait))?,
+                None => apply_timeout(TimeoutType::Wait, instant, self.wait_for_slot()).await?,
+            };
+            if let Some(obj) = self
+                .fill_slot(slot, handoff, instant, &mut checkout)
+                .await?
+            {
+                return Ok(obj);
+            }
+        }
+    }
+
+    /// Turns an acquired slot into an [`Object`] by recycling the handed over
+    /// object or an idle one, or by creating a new one.
+    ///
+    /// Returns [`None`] if there is neither an idle object nor room for a new
+    /// one, so the slot has been given up.
+    async fn fill_slot(
+        &self,
+        slot: SlotGuard<'_, ObjectInner<M::Type>>,
+        mut handoff: Option<ObjectInner<M::Type>>,
+        instant: Option<Instant>,
+        checkout: &mut Option<Checkout>,
+    ) -> Result<Option<Object<M>>, PoolError<M::Error>> {
         loop {
-            let inner_obj = if let Some(inner_obj) = self.inner.slots.vec.pop().await {
-                self.try_recycle(instant, inner_obj).await?
+            let inner_obj = match handoff.take() {
+                Some(inner_obj) => Some(inner_obj),
+                None => self.inner.slots.pop().await,
+            };
+            let (inner_obj, stage) = if let Some(inner_obj) = inner_obj {
+                let stage = self.inner.counts.enter(Stage::Recycling);
+                (self.try_recycle(instant, inner_obj).await?, stage)
+            } else if let Some(size) = self.inner.reserve() {
+                let stage = self.inner.counts.enter(Stage::Creating);
+                (Some(self.try_create(instant, size).await?), stage)
             } else {
-                Some(self.try_create(instant).await?)
+                // Either the pool has been shrunk and the objects still in use
+                // need to be returned first, or an object has been added via
+                // `Pool::add()` in the meantime. Giving up the slot pays off
+                // the shrinking in the former case, while the object will be
+                // found once another slot has been acquired in the latter.
+                return Ok(None);
             };
             if let Some(inner_obj) = inner_obj {
-                permit.forget();
-                break Ok(Object::new(inner_obj, &self.inner));
+                stage.finish();
+                slot.forget();
+                return Ok(Some(Object::new(inner_obj, &self.inner, checkout.take())));
+            }
+        }
+    }
+
+    /// Retrieves `n` [`Object`]s from this [`Pool`] at once or waits until
+    /// that many become available.
+    ///
+    /// Unlike calling [`Pool::get()`] `n` times, the slots for all objects
+    /// are acquired atomically. So multiple tasks each needing several objects
+    /// can't deadlock by holding only a part of them. If any of the objects
+    /// can't be retrieved, the ones retrieved so far are returned to the pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoolError::ExceedsMaxSize`] right away if `n` is greater than
+    /// the maximum size of this [`Pool`], as this could never succeed. See
+    /// [`PoolError`] for the other errors.
+    #[track_caller]
+    pub fn get_many(
+        &self,
+        n: usize,
+        timeout: Option<Duration>,
+    ) -> impl Future<Output = Result<Vec<Object<M>>, PoolError<M::Error>>> + '_ {
+        // Checked before capturing anything, as `n` is only bounded by the
+        // caller.
+        let max_size = self.inner.slots.capacity();
+        let request = match u32::try_from(n) {
+            Ok(permits) if n <= max_size => {
+                let mut checkouts = Vec::with_capacity(n);
+                for _ in 0..n {
+                    checkouts.push(Checkout::capture(self.inner.config.load().leak_threshold));
+                }
+                Ok((permits, checkouts))
+            }
+            _ => Err(PoolError::ExceedsMaxSize {
+                requested: n,
+                max_size,
+            }),
+        };
+        async move {
+            let start = Instant::now();
+            let res = match request {
+                Ok((permits, checkouts)) => {
+                    self.get_many_inner(start, permits, timeout, checkouts)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+
+            self.inner.metrics.record_waiting(start);
+
+            res.map_err(|error| {
+                let _ = self
+                    .inner
+                    .metrics
+                    .failure_count
+                    .fetch_add(1, Ordering::Relaxed);
+                error
+            })
+        }
+    }
+
+    async fn get_many_inner(
+        &self,
+        now: Instant,
+        permits: u32,
+        timeouts: Option<Duration>,
+        checkouts: Vec<Option<Checkout>>,
+    ) -> Result<Vec<Object<M>>, PoolError<M::Error>> {
+        if permits == 0 {
+            return Ok(Vec::new());
+        }
+        let non_blocking = match timeouts {
+            Some(t) => t.is_zero(),
+            None => false,
+        };
+        let instant = timeouts.and_then(|d| now.checked_add(d));
+
+        let slots = &self.inner.slots;
+        let permit = {
+            let _batch = slots.wait_batch();
+            match slots.semaphore.try_acquire_many(permits) {
+                Ok(permit) => permit,
+                Err(TryAcquireError::Closed) => return Err(PoolError::Closed),
+                Err(TryAcquireError::NoPermits) if non_blocking => {
+                    return Err(PoolError::Timeout(TimeoutType::Wait));
+                }
+                Err(TryAcquireError::NoPermits) => {
+                    let _waiting = self.inner.counts.enter(Stage::Waiting);
+                    apply_timeout(TimeoutType::Wait, instant, async {
+                        slots
+                            .semaphore
+                            .acquire_many(permits)
+                            .await
+                            .map_err(|_| PoolError::Closed)
+                    })
+                    .await?
+                }
             }
+        };
+        permit.forget();
+        // Every slot is released again unless it has been filled by an object.
+        let mut slot_guards: Vec<_> = checkouts.iter().map(|_| SlotGuard { slots }).collect();
+
+        let mut objs = Vec::with_capacity(checkouts.len());
+        for checkout in checkouts {
+            let acquired = (slot_guards.pop().unwrap(), None);
+            objs.push(
+                self.get_object(Some(acquired), non_blocking, instant, checkout)
+                    .await?,
+            );
+        }
+        Ok(objs)
+    }
+
+    /// Acquires a permit or takes a cached object from any thread without
+    /// waiting.
+    fn try_acquire_slot(&self) -> Result<Option<Acquired<'_, M::Type>>, PoolError<M::Error>> {
+        let slots = &self.inner.slots;
+        match slots.semaphore.try_acquire() {
+            Ok(permit) => Ok(Some((SlotGuard::new(slots, permit), None))),
+            Err(TryAcquireError::Closed) => Err(PoolError::Closed),
+            Err(TryAcquireError::NoPermits) => Ok(slots.steal_cached().map(|inner_obj| {
+                let slot = SlotGuard { slots };
+                (slot, Some(inner_obj))
+            })),
+        }
+    }
+
+    /// Waits for either a permit or an object handed over directly by
+    /// [`Slots::put()`], whichever comes first.
+    async fn wait_for_slot(&self) -> Result<Acquired<'_, M::Type>, PoolError<M::Error>> {
+        if let Some(slot) = self.try_acquire_slot()? {
+            return Ok(slot);
         }
+
+        let _waiting = self.inner.counts.enter(Stage::Waiting);
+        let slots = &self.inner.slots;
+        let mut waiter = Waiter {
+            slots,
+            rx: Some(slots.wait()),
+        };
+        // An object might have been cached right before the waiter was
+        // registered.
+        if let Some(inner_obj) = slots.steal_cached() {
+            let slot = SlotGuard { slots };
+            return Ok((slot, Some(inner_obj)));
+        }
+        let mut acquire = pin!(slots.semaphore.acquire());
+        poll_fn(|cx| {
+            if let Poll::Ready(inner_obj) = waiter.poll_recv(cx) {
+                let slot = SlotGuard { slots };
+                return Poll::Ready(Ok((slot, Some(inner_obj))));
+            }
+            acquire.as_mut().poll(cx).map(|res| match res {
+                Ok(permit) => Ok((SlotGuard::new(slots, permit), None)),
+                Err(_) => Err(PoolError::Closed),
+            })
+        })
+        .await
     }
 
     #[inline]
     async fn try_recycle(
         &self,
         instant: Option<Instant>,
-        inner_obj: M::Type,
-    ) -> Result<Option<M::Type>, PoolError<M::Error>> {
+        inner_obj: ObjectInner<M::Type>,
+    ) -> Result<Option<ObjectInner<M::Type>>, PoolError<M::Error>> {
         apply_timeout(TimeoutType::Recycle, instant, async move {
-            Ok::<_, M::Error>(self.inner.manager.recycle(inner_obj).await)
+            let ObjectInner {
+                mut obj,
+                mut metrics,
+                size,
+            } = inner_obj;
+            let hooks = &self.inner.hooks;
+            let pool_metrics = &self.inner.metrics;
+            if hooks.pre_recycle.apply(&mut obj, &metrics).await.is_err() {
+                let _ = pool_metrics
+                    .pre_recycle_failure_count
+                    .fetch_add(1, Ordering::Relaxed);
+                return Ok::<_, PoolError<M::Error>>(None);
+            }
+            let Some(mut obj) = self.inner.manager.recycle(obj).await else {
+                let _ = pool_metrics
+                    .recycle_failure_count
+                    .fetch_add(1, Ordering::Relaxed);
+                return Ok(None);
+            };
+            metrics.recycled = Some(Instant::now());
+            metrics.recycle_count += 1;
+            if hooks.post_recycle.apply(&mut obj, &metrics).await.is_err() {
+                let _ = pool_metrics
+                    .post_recycle_failure_count
+                    .fetch_add(1, Ordering::Relaxed);
+                return Ok(None);
+            }
+            Ok(Some(ObjectInner { obj, metrics, size }))
         })
         .await
     }
 
     #[inline]
-    async fn try_create(&self, instant: Option<Instant>) -> Result<M::Type, PoolError<M::Error>> {
-        apply_timeout(TimeoutType::Create, instant, self.inner.manager.create()).await
+    async fn try_create(
+        &self,
+        instant: Option<Instant>,
+        size: SizeGuard,
+    ) -> Result<ObjectInner<M::Type>, PoolError<M::Error>> {
+        apply_timeout(TimeoutType::Create, instant, async {
+            let pool_metrics = &self.inner.metrics;
+            let obj = self.inner.manager.create().await.map_err(|e| {
+                let _ = pool_metrics
+                    .create_failure_count
+                    .fetch_add(1, Ordering::Relaxed);
+                PoolError::Backend(e)
+            })?;
+            let mut inner_obj = ObjectInner::new(obj, size);
+            self.inner
+                .hooks
+                .post_create
+                .apply(&mut inner_obj.obj, &inner_obj.metrics)
+                .await
+                .map_err(|e| {
+                    let _ = pool_metrics
+                        .post_create_failure_count
+                        .fetch_add(1, Ordering::Relaxed);
+                    PoolError::PostCreateHook(e)
+                })?;
+            Ok::<_, PoolError<M::Error>>(inner_obj)
+        })
+        .await
+    }
+
+    /// Retrieves an [`Object`] from this [`Pool`] and passes it to the given
+    /// closure.
+    ///
+    /// The object is returned to the [`Pool`] if the closure succeeds. If it
+    /// fails, panics or the returned future is dropped before completion, the
+    /// object is likely left in an inconsistent state and is discarded using
+    /// [`Object::discard()`] instead.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use deadpool::{NativeManager, PoolError};
+    /// # struct Manager;
+    /// # impl NativeManager for Manager {
+    /// #     type Type = Vec<u8>;
+    /// #     type Error = Infallible;
+    /// #     async fn create(&self) -> Result<Vec<u8>, Infallible> {
+    /// #         Ok(Vec::new())
+    /// #     }
+    /// #     async fn recycle(&self, obj: Vec<u8>) -> Option<Vec<u8>> {
+    /// #         Some(obj)
+    /// #     }
+    /// # }
+    /// #[derive(Debug)]
+    /// enum Error {
+    ///     Pool(PoolError<Infallible>),
+    ///     TooLong,
+    /// }
+    ///
+    /// impl From<PoolError<Infallible>> for Error {
+    ///     fn from(e: PoolError<Infallible>) -> Self {
+    ///         Self::Pool(e)
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let pool = deadpool::Pool::builder(Manager).max_size(1).build().unwrap();
+    /// let res = pool
+    ///     .with(|buf| {
+    ///         Box::pin(async move {
+    ///             buf.extend_from_slice(b"hello");
+    ///             if buf.len() > 3 {
+    ///                 return Err(Error::TooLong);
+    ///             }
+    ///             Ok(buf.len())
+    ///         })
+    ///     })
+    ///     .await;
+    /// assert!(matches!(res, Err(Error::TooLong)));
+    /// // The half-written buffer has been discarded.
+    /// assert_eq!(pool.status().size, 0);
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the closure or the [`PoolError`] of retrieving
+    /// the object converted into it.
+    #[track_caller]
+    pub fn with<'p, R, E, F>(&'p self, f: F) -> impl Future<Output = Result<R, E>> + 'p
+    where
+        F: for<'a> FnOnce(&'a mut M::Type) -> WithFuture<'a, R, E> + 'p,
+        R: 'p,
+        E: From<PoolError<M::Error>> + 'p,
+    {
+        let get = self.get();
+        async move {
+            let mut guard = DiscardGuard(Some(get.await?));
+            let res = f(guard.0.as_mut().unwrap()).await;
+            if res.is_ok() {
+                // Return the object to the pool as usual.
+                drop(guard.0.take());
+            }
+            res
+        }
+    }
+
+    /// Adds an object which has been created outside of this [`Pool`] as
+    /// idle object.
+    ///
+    /// This is the counterpart of [`Object::take()`]: Taking an object and
+    /// adding it again leaves the [`Pool`] in the same state as returning it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the object back if this [`Pool`] has been closed or already
+    /// contains its maximum number of objects, including objects which are
+    /// currently checked out or being created.
+    pub fn add(&self, obj: M::Type) -> Result<(), M::Type> {
+        if self.is_closed() {
+            return Err(obj);
+        }
+        let Some(size) = self.inner.reserve() else {
+            return Err(obj);
+        };
+        self.inner.slots.push(ObjectInner::new(obj, size));
+        self.inner.counts.notify();
+        Ok(())
+    }
+
+    /// Retains only the idle objects specified by the given function.
+    ///
+    /// This function is typically used to remove objects from the pool which
+    /// have been idle for too long or have been recycled too many times, e.g.
+    /// `pool.retain(|_, metrics| metrics.last_used() < max_idle).await`.
+    ///
+    /// Objects which are currently checked out are not affected.
+    pub async fn retain(&self, mut f: impl FnMut(&M::Type, &ObjectMetrics) -> bool) {
+        let slots = &self.inner.slots;
+        slots.flush_cache();
+        for _ in 0..slots.len() {
+            let Some(inner_obj) = slots.pop().await else {
+                break;
+            };
+            if f(&inner_obj.obj, &inner_obj.metrics) {
+                slots.push(inner_obj);
+            }
+        }
+        self.inner.counts.notify();
     }
 
     /// Closes this [`Pool`].
@@ -171,7 +567,10 @@ impl<M: Manager> Pool<M> {
     /// This operation resizes the pool to 0.
     pub async fn close(&self) {
         self.inner.slots.semaphore.close();
-        while self.inner.slots.vec.pop().await.is_some() {}
+        self.inner.slots.clear_waiters();
+        self.inner.slots.flush_cache();
+        while self.inner.slots.pop().await.is_some() {}
+        self.inner.counts.notify();
     }
 
     /// Indicates whether this [`Pool`] has been closed.
@@ -180,20 +579,93 @@ impl<M: Manager> Pool<M> {
         self.inner.slots.semaphore.is_closed()
     }
 
+    /// Applies a new [`PoolConfig`] to this running [`Pool`] without dropping
+    /// any of its objects unless it's shrunk, and returns the previous one.
+    ///
+    /// The new timeouts and leak threshold apply to subsequent calls of
+    /// [`Pool::get()`]. Growing the [`Pool`] lets waiting tasks create new
+    /// objects right away. Shrinking it drops idle objects exceeding the new
+    /// [`PoolConfig::max_size`] right away, while checked out objects are
+    /// dropped as they are returned until the [`Pool`] has shrunk. Cached
+    /// objects are moved back into the shared queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`BuildError`] if the new [`PoolConfig`] is invalid, see
+    /// [`PoolBuilder::build()`], or if it changes the
+    /// [`PoolConfig::queue_mode`], which is fixed once the [`Pool`] has been
+    /// built.
+    pub async fn reconfigure(&self, config: PoolConfig) -> Result<PoolConfig, BuildError> {
+        config.validate()?;
+        let slots = &self.inner.slots;
+        let previous = {
+            let _lock = self.inner.reconfigure.lock().unwrap();
+            let previous = self.inner.config.load();
+            if config.queue_mode != previous.queue_mode {
+                return Err(BuildError::QueueModeChanged);
+            }
+            self.inner.config.store(config);
+            slots.set_cache_capacity(config.thread_cache);
+            slots.resize(config.max_size);
+            previous
+        };
+        slots.flush_cache();
+        while self.inner.size.load(Ordering::Relaxed) > slots.capacity() {
+            // Idle objects don't hold any permits, so they are just dropped.
+            if slots.pop().await.is_none() {
+                break;
+            }
+        }
+        self.inner.counts.notify();
+        Ok(previous)
+    }
+
     /// Retrieves [`Status`] of this [`Pool`].
     #[must_use]
     pub fn status(&self) -> Status {
-        let size = self.inner.slots.vec.len();
-        let max_size = self.inner.slots.vec.capacity();
-        let available = self.inner.slots.semaphore.available_permits();
+        let size = self.inner.slots.len();
+        let max_size = self.inner.slots.capacity();
+        let available = self.inner.slots.available_permits() + self.inner.slots.cached();
+        let counts = self.inner.counts.get();
         Status {
             max_size,
             size,
             available,
+            in_use: counts.in_use,
+            creating: counts.creating,
+            recycling: counts.recycling,
+            waiting: counts.waiting,
         }
     }
 
-    /// Returns [`Manager`] of this [`Pool`].
+    /// Returns a [`StatusWatch`] receiving the [`Status`] of this [`Pool`]
+    /// whenever it changes, but at most once per `interval`.
+    #[must_use]
+    pub fn watch_status(&self, interval: Duration) -> StatusWatch<M> {
+        StatusWatch::new(self, interval)
+    }
+
+    /// Retrieves the [`HighWaterMarks`] of this [`Pool`].
+    #[must_use]
+    pub fn high_water_marks(&self) -> HighWaterMarks {
+        self.inner.counts.high_water()
+    }
+
+    /// Resets the [`HighWaterMarks`] of this [`Pool`] to the current counts
+    /// and returns the previous ones, e.g. once they have been reported.
+    #[must_use]
+    pub fn reset_high_water_marks(&self) -> HighWaterMarks {
+        self.inner.counts.reset_high_water()
+    }
+
+    /// Returns whether retrying after the given `error` returned by this
+    /// [`Pool`] might succeed, see [`PoolError::is_retryable()`].
+    #[must_use]
+    pub fn is_retryable(&self, error: &PoolError<M::Error>) -> bool {
+        error.is_retryable(&self.inner.manager)
+    }
+
+    /// Returns [`NativeManager`] of this [`Pool`].
     #[must_use]
     pub fn manager(&self) -> &M {
         &self.inner.manager
@@ -201,18 +673,131 @@ impl<M: Manager> Pool<M> {
 }
 
 #[derive(Debug)]
-pub(crate) struct PoolInner<M: Manager + ?Sized> {
-    pub(crate) slots: Slots<M::Type>,
-    config: PoolConfig,
-    metrics: PoolMetrics,
+pub(crate) struct PoolInner<M: NativeManager + ?Sized> {
+    pub(crate) slots: Slots<ObjectInner<M::Type>>,
+    pub(crate) config: AtomicCell<PoolConfig>,
+
+    /// Serializes calls of [`Pool::reconfigure()`].
+    reconfigure: Mutex<()>,
+    pub(crate) metrics: Arc<PoolMetrics>,
+    hooks: Hooks<M>,
+    pub(crate) leak_callback: Option<Arc<LeakCallback>>,
+
+    /// Number of objects existing in this pool, see [`SizeGuard`].
+    size: Arc<AtomicUsize>,
+
+    /// Objects and tasks in the different stages of [`Pool::get()`].
+    pub(crate) counts: StatusCounts,
+
     manager: M,
 }
 
-impl<M: Manager + ?Sized> PoolInner<M> {
-    pub(crate) fn return_object(&self, inner: M::Type, start: Instant) {
+impl<M: NativeManager + ?Sized> PoolInner<M> {
+    pub(crate) fn return_object(&self, mut inner: ObjectInner<M::Type>, start: Instant) {
+        self.counts.release_in_use();
+        self.metrics.record_active(start);
+        inner.metrics.last_used = Some(Instant::now());
+        if self.size.load(Ordering::Relaxed) > self.slots.capacity() {
+            // The pool has been shrunk while this object was in use.
+            drop(inner);
+            self.slots.add_permits(1);
+        } else {
+            self.slots.put(inner);
+        }
+        self.counts.notify();
+    }
+
+    /// Counts a new object towards the size of this pool unless it's full.
+    fn reserve(&self) -> Option<SizeGuard> {
+        SizeGuard::reserve(&self.size, self.slots.capacity())
+    }
+
+    pub(crate) fn discard_object(&self, inner: ObjectInner<M::Type>, start: Instant) {
+        self.counts.release_in_use();
         self.metrics.record_active(start);
-        if self.slots.vec.push_blocking(inner).is_ok() {
-            self.slots.semaphore.add_permits(1);
+        let _ = self.metrics.discard_count.fetch_add(1, Ordering::Relaxed);
+        drop(inner);
+        self.slots.add_permits(1);
+        self.counts.notify();
+    }
+}
+
+/// The boxed future returned by the closure passed to [`Pool::with()`].
+pub type WithFuture<'a, R, E> = Pin<Box<dyn Future<Output = Result<R, E>> + Send + 'a>>;
+
+/// Discards the contained [`Object`] when dropped, e.g. while unwinding.
+struct DiscardGuard<M: NativeManager>(Option<Object<M>>);
+
+impl<M: NativeManager> Drop for DiscardGuard<M> {
+    fn drop(&mut self) {
+        if let Some(obj) = self.0.take() {
+            Object::discard(obj);
+        }
+    }
+}
+
+/// Slot acquired by a task calling [`Pool::get()`] and possibly an object
+/// handed over along with it.
+type Acquired<'a, T> = (SlotGuard<'a, ObjectInner<T>>, Option<ObjectInner<T>>);
+
+/// Right to hold one object of the pool, which is given back when dropped.
+///
+/// This is either a [`SemaphorePermit`] or the permit of an object that was
+/// handed over directly by [`Slots::put()`].
+#[must_use]
+struct SlotGuard<'a, T> {
+    slots: &'a Slots<T>,
+}
+
+impl<'a, T> SlotGuard<'a, T> {
+    fn new(slots: &'a Slots<T>, permit: SemaphorePermit<'a>) -> Self {
+        permit.forget();
+        Self { slots }
+    }
+
+    /// Keeps the slot occupied, as it's now held by an [`Object`].
+    fn forget(self) {
+        mem::forget(self);
+    }
+}
+
+impl<T> Drop for SlotGuard<'_, T> {
+    fn drop(&mut self) {
+        self.slots.add_permits(1);
+    }
+}
+
+/// Task waiting for an object handed over by [`Slots::put()`].
+///
+/// If dropped after an object has been sent, e.g. because the waiting task
+/// acquired a permit first or timed out, the object is returned to the pool.
+struct Waiter<'a, T> {
+    slots: &'a Slots<T>,
+    rx: Option<oneshot::Receiver<T>>,
+}
+
+impl<T> Waiter<'_, T> {
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<T> {
+        let Some(rx) = &mut self.rx else {
+            return Poll::Pending;
+        };
+        let res = ready!(Pin::new(rx).poll(cx));
+        self.rx = None;
+        match res {
+            Ok(value) => Poll::Ready(value),
+            // The pool has been closed and the permit will tell so.
+            Err(_) => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Drop for Waiter<'_, T> {
+    fn drop(&mut self) {
+        if let Some(mut rx) = self.rx.take() {
+            rx.close();
+            if let Ok(value) = rx.try_recv() {
+                self.slots.put(value);
+            }
         }
     }
 }
@@ -220,13 +805,12 @@ impl<M: Manager + ?Sized> PoolInner<M> {
 async fn apply_timeout<O, E>(
     timeout_type: TimeoutType,
     instant: Option<Instant>,
-    future: impl Future<Output = Result<O, impl Into<PoolError<E>>>>,
+    future: impl Future<Output = Result<O, PoolError<E>>>,
 ) -> Result<O, PoolError<E>> {
     match instant {
-        None => future.await.map_err(Into::into),
+        None => future.await,
         Some(instant) => tokio::time::timeout_at(instant, future)
             .await
-            .map_err(|_| PoolError::Timeout(timeout_type))?
-            .map_err(Into::into),
+            .map_err(|_| PoolError::Timeout(timeout_type))?,
     }
 }
@@ -0,0 +1,250 @@
+use std::{
+    fmt, mem,
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use tokio::{sync::watch, time::Instant};
+
+use crate::{pool::PoolInner, NativeManager, Pool};
+
+/// The current pool status.
+#[derive(Clone, Copy, Debug)]
+pub struct Status {
+    /// The maximum size of the pool.
+    pub max_size: usize,
+
+    /// The current items idle in the pool.
+    pub size: usize,
+
+    /// The permits available from the pool.
+    pub available: usize,
+
+    /// The objects currently checked out of the pool.
+    pub in_use: usize,
+
+    /// The objects currently being created.
+    pub creating: usize,
+
+    /// The idle objects currently being recycled.
+    pub recycling: usize,
+
+    /// The tasks currently waiting for an object to become available.
+    pub waiting: usize,
+}
+
+/// Highest numbers of objects and tasks in the different stages of
+/// [`Pool::get()`] since the [`Pool`] has been built or the marks have been
+/// reset by [`Pool::reset_high_water_marks()`].
+///
+/// Unlike a [`Status`] snapshot, these also capture short bursts which are
+/// over before anyone gets to see them.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HighWaterMarks {
+    /// Highest [`Status::in_use`].
+    pub in_use: usize,
+
+    /// Highest [`Status::creating`].
+    pub creating: usize,
+
+    /// Highest [`Status::recycling`].
+    pub recycling: usize,
+
+    /// Highest [`Status::waiting`].
+    pub waiting: usize,
+}
+
+/// Numbers of objects and tasks in the different stages of [`Pool::get()`],
+/// which are kept together so [`Pool::status()`] sees a consistent snapshot.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Counts {
+    pub(crate) in_use: usize,
+    pub(crate) creating: usize,
+    pub(crate) recycling: usize,
+    pub(crate) waiting: usize,
+    high_water: HighWaterMarks,
+}
+
+impl Counts {
+    fn stage(&mut self, stage: Stage) -> (&mut usize, &mut usize) {
+        match stage {
+            Stage::Waiting => (&mut self.waiting, &mut self.high_water.waiting),
+            Stage::Creating => (&mut self.creating, &mut self.high_water.creating),
+            Stage::Recycling => (&mut self.recycling, &mut self.high_water.recycling),
+        }
+    }
+
+    fn enter(&mut self, stage: Stage) {
+        let (count, high_water) = self.stage(stage);
+        *count += 1;
+        *high_water = (*high_water).max(*count);
+    }
+
+    fn leave(&mut self, stage: Stage) {
+        *self.stage(stage).0 -= 1;
+    }
+
+    fn finish(&mut self, stage: Stage) {
+        self.leave(stage);
+        self.in_use += 1;
+        self.high_water.in_use = self.high_water.in_use.max(self.in_use);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Stage {
+    Waiting,
+    Creating,
+    Recycling,
+}
+
+/// [`Counts`] of a [`Pool`] along with the channel notifying
+/// [`StatusWatch`]es about changes of its [`Status`].
+///
+/// All counts share a single lock, which is only held while updating a few
+/// integers, so a task moving from one [`Stage`] to the next is never seen
+/// in both or neither of them.
+#[derive(Debug)]
+pub(crate) struct StatusCounts {
+    counts: Mutex<Counts>,
+    changed: watch::Sender<()>,
+}
+
+impl StatusCounts {
+    pub(crate) fn new() -> Self {
+        Self {
+            counts: Mutex::new(Counts::default()),
+            changed: watch::channel(()).0,
+        }
+    }
+
+    /// Returns a consistent snapshot of the current [`Counts`].
+    pub(crate) fn get(&self) -> Counts {
+        *self.counts.lock().unwrap()
+    }
+
+    /// Returns the current [`HighWaterMarks`].
+    pub(crate) fn high_water(&self) -> HighWaterMarks {
+        self.counts.lock().unwrap().high_water
+    }
+
+    /// Counts a task in the given [`Stage`] until the returned guard is
+    /// dropped.
+    pub(crate) fn enter(&self, stage: Stage) -> StageGuard<'_> {
+        self.counts.lock().unwrap().enter(stage);
+        self.notify();
+        StageGuard {
+            counts: self,
+            stage,
+        }
+    }
+
+    /// Stops counting an object as in use once it has been returned, taken
+    /// or discarded. The caller has to [`notify()`] once it's done.
+    ///
+    /// [`notify()`]: StatusCounts::notify
+    pub(crate) fn release_in_use(&self) {
+        self.counts.lock().unwrap().in_use -= 1;
+    }
+
+    /// Resets the [`HighWaterMarks`] to the current counts and returns the
+    /// previous ones.
+    pub(crate) fn reset_high_water(&self) -> HighWaterMarks {
+        let mut counts = self.counts.lock().unwrap();
+        let current = HighWaterMarks {
+            in_use: counts.in_use,
+            creating: counts.creating,
+            recycling: counts.recycling,
+            waiting: counts.waiting,
+        };
+        mem::replace(&mut counts.high_water, current)
+    }
+
+    /// Wakes up all [`StatusWatch`]es after the [`Status`] has changed.
+    pub(crate) fn notify(&self) {
+        if self.changed.receiver_count() > 0 {
+            // Fails without any effect if the last watch has just gone away.
+            let _ = self.changed.send(());
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<()> {
+        self.changed.subscribe()
+    }
+}
+
+/// Counts a task in a [`Stage`] of [`Pool::get()`] until it's dropped.
+pub(crate) struct StageGuard<'a> {
+    counts: &'a StatusCounts,
+    stage: Stage,
+}
+
+impl StageGuard<'_> {
+    /// Counts the object created or recycled in this [`Stage`] as in use.
+    pub(crate) fn finish(self) {
+        self.counts.counts.lock().unwrap().finish(self.stage);
+        self.counts.notify();
+        mem::forget(self);
+    }
+}
+
+impl Drop for StageGuard<'_> {
+    fn drop(&mut self) {
+        self.counts.counts.lock().unwrap().leave(self.stage);
+        self.counts.notify();
+    }
+}
+
+/// Receiver of [`Status`] changes returned by [`Pool::watch_status()`].
+pub struct StatusWatch<M: NativeManager> {
+    pool: Weak<PoolInner<M>>,
+    changed: watch::Receiver<()>,
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl<M: NativeManager> fmt::Debug for StatusWatch<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatusWatch")
+            .field("interval", &self.interval)
+            .field("last", &self.last)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: NativeManager> StatusWatch<M> {
+    pub(crate) fn new(pool: &Pool<M>, interval: Duration) -> Self {
+        Self {
+            pool: Arc::downgrade(&pool.inner),
+            changed: pool.inner.counts.subscribe(),
+            interval,
+            last: None,
+        }
+    }
+
+    /// Waits until the [`Status`] of the [`Pool`] has changed and returns the
+    /// new one.
+    ///
+    /// At most one [`Status`] is returned per `interval` passed to
+    /// [`Pool::watch_status()`]. Changes in between are coalesced, so use
+    /// [`Pool::high_water_marks()`] in order to see bursts.
+    ///
+    /// Returns [`None`] once the [`Pool`] has been closed or dropped.
+    pub async fn changed(&mut self) -> Option<Status> {
+        if let Some(last) = self.last {
+            tokio::time::sleep_until(last + self.interval).await;
+        }
+        // The last change might have been closing the pool.
+        let pending = self.changed.has_changed().ok()?;
+        if !pending && self.pool().map_or(true, |pool| pool.is_closed()) {
+            return None;
+        }
+        self.changed.changed().await.ok()?;
+        self.last = Some(Instant::now());
+        self.pool().map(|pool| pool.status())
+    }
+
+    fn pool(&self) -> Option<Pool<M>> {
+        self.pool.upgrade().map(|inner| Pool { inner })
+    }
+}
@@ -0,0 +1,43 @@
+//! Synchronization primitives used by the [`ArrayQueue`], which are replaced
+//! by the ones provided by [`loom`] when compiling with `--cfg loom`.
+//!
+//! [`ArrayQueue`]: crate::array_queue::ArrayQueue
+//! [`loom`]: https://docs.rs/loom
+
+#[cfg(loom)]
+pub(crate) use loom::{cell::UnsafeCell, hint, sync::atomic, thread};
+
+#[cfg(not(loom))]
+pub(crate) use std::{hint, sync::atomic};
+
+#[cfg(not(loom))]
+pub(crate) use tokio::task::yield_now;
+
+/// Yields execution back to the loom scheduler.
+#[cfg(loom)]
+pub(crate) async fn yield_now() {
+    thread::yield_now();
+}
+
+/// [`std::cell::UnsafeCell`] exposing the closure based API of
+/// `loom::cell::UnsafeCell`.
+#[cfg(not(loom))]
+#[derive(Debug)]
+pub(crate) struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    pub(crate) fn new(data: T) -> Self {
+        Self(std::cell::UnsafeCell::new(data))
+    }
+
+    #[inline]
+    pub(crate) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        f(self.0.get())
+    }
+
+    #[inline]
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
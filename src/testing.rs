@@ -0,0 +1,364 @@
+//! Utilities for testing code which uses a [`Pool`].
+//!
+//! This module is only available with the `testing` feature enabled and
+//! provides a [`MockManager`] whose behavior can be scripted step by step as
+//! well as assertion helpers for [`Status`] and [`PoolMetrics`].
+//!
+//! ```rust
+//! use deadpool::{
+//!     testing::{assert_status, MockManager, Step},
+//!     Pool, PoolError,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let manager = MockManager::new();
+//! manager.script_create([Step::Err, Step::Ok]);
+//! let pool = Pool::builder(manager.clone()).max_size(1).build().unwrap();
+//!
+//! assert!(matches!(pool.get().await, Err(PoolError::Backend(_))));
+//! let obj = pool.get().await.unwrap();
+//! assert_status(&pool).size(0).available(0);
+//! drop(obj);
+//! assert_status(&pool).size(1).available(1);
+//! assert_eq!(manager.create_calls(), 2);
+//! # }
+//! ```
+
+// The assertion helpers are called for their side effects and return `Self`
+// only to allow chaining.
+#![allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::sync::Notify;
+
+use crate::{NativeManager, Pool, PoolMetrics, Status};
+
+/// A single scripted outcome of [`MockManager::create()`] or
+/// [`MockManager::recycle()`].
+///
+/// [`MockManager::create()`]: NativeManager::create
+/// [`MockManager::recycle()`]: NativeManager::recycle
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Step {
+    /// Succeed immediately.
+    Ok,
+
+    /// Fail immediately. Creating fails with a [`MockError`] and recycling
+    /// discards the object.
+    Err,
+
+    /// Succeed after the given [`Duration`].
+    Delay(Duration),
+
+    /// Never complete.
+    Hang,
+
+    /// Panic.
+    Panic,
+}
+
+impl Step {
+    async fn run(self) -> Result<(), MockError> {
+        match self {
+            Self::Ok => Ok(()),
+            Self::Err => Err(MockError),
+            Self::Delay(d) => {
+                tokio::time::sleep(d).await;
+                Ok(())
+            }
+            Self::Hang => std::future::pending().await,
+            Self::Panic => panic!("MockManager scripted to panic"),
+        }
+    }
+}
+
+/// Error returned by a [`MockManager`] scripted with [`Step::Err`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MockError;
+
+impl fmt::Display for MockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MockManager scripted to fail")
+    }
+}
+
+impl std::error::Error for MockError {}
+
+#[derive(Debug, Default)]
+struct Script {
+    steps: Mutex<VecDeque<Step>>,
+    notify: Notify,
+    calls: AtomicUsize,
+}
+
+impl Script {
+    fn push(&self, steps: impl IntoIterator<Item = Step>) {
+        self.steps.lock().unwrap().extend(steps);
+        self.notify.notify_waiters();
+    }
+
+    async fn next(&self, wait: bool) -> Step {
+        let _ = self.calls.fetch_add(1, Ordering::Relaxed);
+        loop {
+            let notified = self.notify.notified();
+            if let Some(step) = self.steps.lock().unwrap().pop_front() {
+                return step;
+            }
+            if !wait {
+                return Step::Ok;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    create: Script,
+    recycle: Script,
+    wait: bool,
+    created: AtomicUsize,
+}
+
+/// Scriptable [`NativeManager`] for testing.
+///
+/// Objects are of type [`usize`] and numbered in the order they were
+/// created. Every call to [`NativeManager::create()`] and
+/// [`NativeManager::recycle()`] consumes the next [`Step`] from the
+/// respective script. Once a script has run out of steps, calls either
+/// succeed (see [`MockManager::new()`]) or wait for more steps to be scripted
+/// (see [`MockManager::blocking()`]).
+///
+/// Cloning a [`MockManager`] returns a handle to the same scripts, so the
+/// manager can be scripted after it has been passed to a [`Pool`].
+#[derive(Clone, Debug, Default)]
+pub struct MockManager {
+    inner: Arc<Inner>,
+}
+
+impl MockManager {
+    /// Creates a new [`MockManager`] which succeeds once its scripts have run
+    /// out of steps.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`MockManager`] which waits for more steps to be
+    /// scripted once its scripts have run out of steps.
+    #[must_use]
+    pub fn blocking() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                wait: true,
+                ..Inner::default()
+            }),
+        }
+    }
+
+    /// Appends the given `steps` to the script for creating objects.
+    pub fn script_create(&self, steps: impl IntoIterator<Item = Step>) {
+        self.inner.create.push(steps);
+    }
+
+    /// Appends the given `steps` to the script for recycling objects.
+    pub fn script_recycle(&self, steps: impl IntoIterator<Item = Step>) {
+        self.inner.recycle.push(steps);
+    }
+
+    /// Returns the number of times [`NativeManager::create()`] was called.
+    #[must_use]
+    pub fn create_calls(&self) -> usize {
+        self.inner.create.calls.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of times [`NativeManager::recycle()`] was called.
+    #[must_use]
+    pub fn recycle_calls(&self) -> usize {
+        self.inner.recycle.calls.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of objects which were created successfully.
+    #[must_use]
+    pub fn created(&self) -> usize {
+        self.inner.created.load(Ordering::Relaxed)
+    }
+}
+
+impl NativeManager for MockManager {
+    type Type = usize;
+    type Error = MockError;
+
+    async fn create(&self) -> Result<usize, MockError> {
+        self.inner.create.next(self.inner.wait).await.run().await?;
+        Ok(self.inner.created.fetch_add(1, Ordering::Relaxed))
+    }
+
+    async fn recycle(&self, obj: usize) -> Option<usize> {
+        self.inner
+            .recycle
+            .next(self.inner.wait)
+            .await
+            .run()
+            .await
+            .ok()
+            .map(|()| obj)
+    }
+}
+
+/// Takes a [`Status`] snapshot of the given `pool` for making assertions.
+///
+/// The snapshot is checked for consistency right away, e.g. the number of
+/// idle objects must never exceed the maximum size of the pool.
+#[track_caller]
+pub fn assert_status<M: NativeManager>(pool: &Pool<M>) -> StatusAssertion {
+    let status = pool.status();
+    assert!(
+        status.size <= status.max_size,
+        "size({}) > max_size({})",
+        status.size,
+        status.max_size
+    );
+    assert!(
+        status.available <= status.max_size,
+        "available({}) > max_size({})",
+        status.available,
+        status.max_size
+    );
+    StatusAssertion { status }
+}
+
+/// Assertions on a [`Status`] snapshot returned by [`assert_status()`].
+#[derive(Clone, Copy, Debug)]
+pub struct StatusAssertion {
+    /// The asserted [`Status`].
+    pub status: Status,
+}
+
+impl StatusAssertion {
+    /// Asserts [`Status::max_size`].
+    #[track_caller]
+    pub fn max_size(self, expected: usize) -> Self {
+        assert_eq!(self.status.max_size, expected, "unexpected max_size");
+        self
+    }
+
+    /// Asserts [`Status::size`].
+    #[track_caller]
+    pub fn size(self, expected: usize) -> Self {
+        assert_eq!(self.status.size, expected, "unexpected size");
+        self
+    }
+
+    /// Asserts [`Status::available`].
+    #[track_caller]
+    pub fn available(self, expected: usize) -> Self {
+        assert_eq!(self.status.available, expected, "unexpected available");
+        self
+    }
+
+    /// Asserts [`Status::in_use`].
+    #[track_caller]
+    pub fn in_use(self, expected: usize) -> Self {
+        assert_eq!(self.status.in_use, expected, "unexpected in_use");
+        self
+    }
+
+    /// Asserts [`Status::creating`].
+    #[track_caller]
+    pub fn creating(self, expected: usize) -> Self {
+        assert_eq!(self.status.creating, expected, "unexpected creating");
+        self
+    }
+
+    /// Asserts [`Status::recycling`].
+    #[track_caller]
+    pub fn recycling(self, expected: usize) -> Self {
+        assert_eq!(self.status.recycling, expected, "unexpected recycling");
+        self
+    }
+
+    /// Asserts [`Status::waiting`].
+    #[track_caller]
+    pub fn waiting(self, expected: usize) -> Self {
+        assert_eq!(self.status.waiting, expected, "unexpected waiting");
+        self
+    }
+}
+
+/// Returns the [`PoolMetrics`] of the given `pool` for making assertions.
+pub fn assert_metrics<M: NativeManager>(pool: &Pool<M>) -> MetricsAssertion<'_> {
+    MetricsAssertion {
+        metrics: pool.metrics(),
+    }
+}
+
+/// Assertions on [`PoolMetrics`] returned by [`assert_metrics()`].
+#[derive(Clone, Copy, Debug)]
+pub struct MetricsAssertion<'a> {
+    /// The asserted [`PoolMetrics`].
+    pub metrics: &'a PoolMetrics,
+}
+
+impl MetricsAssertion<'_> {
+    /// Asserts [`PoolMetrics::failure_count()`].
+    #[track_caller]
+    pub fn failure_count(self, expected: usize) -> Self {
+        assert_eq!(
+            self.metrics.failure_count(),
+            expected,
+            "unexpected failure_count"
+        );
+        self
+    }
+
+    /// Asserts [`PoolMetrics::leak_count()`].
+    #[track_caller]
+    pub fn leak_count(self, expected: usize) -> Self {
+        assert_eq!(self.metrics.leak_count(), expected, "unexpected leak_count");
+        self
+    }
+
+    /// Asserts [`PoolMetrics::discard_count()`].
+    #[track_caller]
+    pub fn discard_count(self, expected: usize) -> Self {
+        assert_eq!(
+            self.metrics.discard_count(),
+            expected,
+            "unexpected discard_count"
+        );
+        self
+    }
+
+    /// Asserts [`PoolMetrics::create_failure_count()`].
+    #[track_caller]
+    pub fn create_failure_count(self, expected: usize) -> Self {
+        assert_eq!(
+            self.metrics.create_failure_count(),
+            expected,
+            "unexpected create_failure_count"
+        );
+        self
+    }
+
+    /// Asserts [`PoolMetrics::recycle_failure_count()`].
+    #[track_caller]
+    pub fn recycle_failure_count(self, expected: usize) -> Self {
+        assert_eq!(
+            self.metrics.recycle_failure_count(),
+            expected,
+            "unexpected recycle_failure_count"
+        );
+        self
+    }
+}
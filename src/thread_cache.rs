@@ -0,0 +1,98 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+use crate::thread_index;
+
+/// Small per-thread stashes of idle objects in front of the [`Slots`].
+///
+/// Objects in the cache keep the permit they were checked out with, so taking
+/// one out of the cache requires neither the shared queue nor the semaphore.
+/// Threads are mapped onto a fixed number of stashes which are protected by
+/// mutexes that are only contended if multiple threads share a stash or a
+/// waiting task steals from it.
+///
+/// [`Slots`]: crate::Slots
+#[derive(Debug)]
+pub(crate) struct ThreadCache<T> {
+    stashes: Box<[Mutex<Vec<T>>]>,
+    capacity: AtomicUsize,
+}
+
+impl<T> ThreadCache<T> {
+    /// Creates a new [`ThreadCache`] holding up to `capacity` objects per
+    /// thread, which is disabled if `capacity` is zero.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            stashes: (0..num_cpus::get())
+                .map(|_| Mutex::new(Vec::new()))
+                .collect(),
+            capacity: AtomicUsize::new(capacity),
+        }
+    }
+
+    /// Changes the number of objects held per thread. Stashes holding more
+    /// objects than that are only trimmed by [`ThreadCache::drain()`].
+    pub(crate) fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+    }
+
+    /// Returns the stash of the current thread, unless the cache is disabled.
+    fn local(&self) -> Option<&Mutex<Vec<T>>> {
+        if self.capacity.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        Some(self.stash())
+    }
+
+    fn stash(&self) -> &Mutex<Vec<T>> {
+        &self.stashes[thread_index() % self.stashes.len()]
+    }
+
+    /// Takes an object from the stash of the current thread, unless it's
+    /// empty or in use by another thread.
+    pub(crate) fn try_take(&self) -> Option<T> {
+        self.local()?.try_lock().ok()?.pop()
+    }
+
+    /// Puts an object into the stash of the current thread, unless it's full
+    /// or in use by another thread.
+    pub(crate) fn try_put(&self, value: T) -> Result<(), T> {
+        let Some(Ok(mut stash)) = self.local().map(Mutex::try_lock) else {
+            return Err(value);
+        };
+        if stash.len() >= self.capacity.load(Ordering::Relaxed) {
+            return Err(value);
+        }
+        stash.push(value);
+        Ok(())
+    }
+
+    /// Takes an object from the stash of the current thread.
+    pub(crate) fn take(&self) -> Option<T> {
+        self.stash().lock().unwrap().pop()
+    }
+
+    /// Takes an object from the stash of any thread.
+    pub(crate) fn steal(&self) -> Option<T> {
+        self.stashes
+            .iter()
+            .find_map(|stash| stash.lock().unwrap().pop())
+    }
+
+    /// Takes all objects from the stashes of all threads.
+    pub(crate) fn drain(&self) -> Vec<T> {
+        self.stashes
+            .iter()
+            .flat_map(|stash| std::mem::take(&mut *stash.lock().unwrap()))
+            .collect()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.stashes
+            .iter()
+            .map(|stash| stash.lock().unwrap().len())
+            .sum()
+    }
+}
@@ -0,0 +1,71 @@
+use std::{env, time::Duration};
+
+use deadpool::{PoolConfig, QueueMode};
+
+fn set(prefix: &str, vars: &[(&str, &str)]) {
+    for (name, value) in vars {
+        env::set_var(format!("{prefix}__{name}"), value);
+    }
+}
+
+#[test]
+fn defaults() {
+    let cfg = PoolConfig::from_env("ENV_DEFAULTS").unwrap();
+    let default = PoolConfig::default();
+    assert_eq!(cfg.max_size, default.max_size);
+    assert_eq!(cfg.timeout, None);
+    assert_eq!(cfg.leak_threshold, None);
+    assert_eq!(cfg.queue_mode, QueueMode::Single);
+    assert_eq!(cfg.thread_cache, 0);
+}
+
+#[test]
+fn all_fields() {
+    set(
+        "ENV_ALL",
+        &[
+            ("MAX_SIZE", "16"),
+            ("TIMEOUT", "30s"),
+            ("LEAK_THRESHOLD", "1500"),
+            ("QUEUE_MODE", "sharded"),
+            ("QUEUE_MODE__SHARDS", "4"),
+            ("THREAD_CACHE", " 2 "),
+        ],
+    );
+    let cfg = PoolConfig::from_env("ENV_ALL").unwrap();
+    assert_eq!(cfg.max_size, 16);
+    assert_eq!(cfg.timeout, Some(Duration::from_secs(30)));
+    assert_eq!(cfg.leak_threshold, Some(Duration::from_millis(1500)));
+    assert_eq!(cfg.queue_mode, QueueMode::Sharded { shards: 4 });
+    assert_eq!(cfg.thread_cache, 2);
+}
+
+#[test]
+fn sharded_per_cpu() {
+    set("ENV_PER_CPU", &[("QUEUE_MODE", "sharded")]);
+    let cfg = PoolConfig::from_env("ENV_PER_CPU").unwrap();
+    assert_eq!(cfg.queue_mode, QueueMode::per_cpu());
+}
+
+#[test]
+fn malformed() {
+    let err = |prefix: &str, vars: &[(&str, &str)]| {
+        set(prefix, vars);
+        PoolConfig::from_env(prefix).unwrap_err()
+    };
+
+    let e = err("ENV_BAD_SIZE", &[("MAX_SIZE", "-1")]);
+    assert_eq!(e.name(), "ENV_BAD_SIZE__MAX_SIZE");
+    assert_eq!(e.value(), "-1");
+    assert!(e.to_string().contains("ENV_BAD_SIZE__MAX_SIZE"));
+
+    let e = err("ENV_BAD_TIMEOUT", &[("TIMEOUT", "30 days")]);
+    assert_eq!(e.name(), "ENV_BAD_TIMEOUT__TIMEOUT");
+    assert_eq!(e.value(), "30 days");
+
+    let e = err("ENV_BAD_MODE", &[("QUEUE_MODE", "random")]);
+    assert_eq!(e.name(), "ENV_BAD_MODE__QUEUE_MODE");
+
+    let e = err("ENV_BAD_SHARDS", &[("QUEUE_MODE__SHARDS", "4")]);
+    assert_eq!(e.name(), "ENV_BAD_SHARDS__QUEUE_MODE__SHARDS");
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use deadpool::{PoolConfig, QueueMode};
+
+#[test]
+fn defaults() {
+    let cfg: PoolConfig = toml::from_str("").unwrap();
+    let default = PoolConfig::default();
+    assert_eq!(cfg.max_size, default.max_size);
+    assert_eq!(cfg.timeout, None);
+    assert_eq!(cfg.leak_threshold, None);
+    assert_eq!(cfg.queue_mode, QueueMode::Single);
+    assert_eq!(cfg.thread_cache, 0);
+}
+
+#[test]
+fn toml() {
+    let cfg: PoolConfig = toml::from_str(
+        r#"
+        max_size = 32
+        timeout = "30s"
+        leak_threshold = 1500
+        queue_mode = { sharded = { shards = 4 } }
+        thread_cache = 2
+        "#,
+    )
+    .unwrap();
+    assert_eq!(cfg.max_size, 32);
+    assert_eq!(cfg.timeout, Some(Duration::from_secs(30)));
+    assert_eq!(cfg.leak_threshold, Some(Duration::from_millis(1500)));
+    assert_eq!(cfg.queue_mode, QueueMode::Sharded { shards: 4 });
+    assert_eq!(cfg.thread_cache, 2);
+}
+
+#[test]
+fn durations() {
+    let timeout = |value: &str| {
+        serde_json::from_str::<PoolConfig>(&format!(r#"{{"timeout": {value}}}"#))
+            .map(|cfg| cfg.timeout)
+    };
+    assert_eq!(timeout("null").unwrap(), None);
+    assert_eq!(timeout("250").unwrap(), Some(Duration::from_millis(250)));
+    assert_eq!(
+        timeout(r#""250""#).unwrap(),
+        Some(Duration::from_millis(250))
+    );
+    assert_eq!(
+        timeout(r#""10 ns""#).unwrap(),
+        Some(Duration::from_nanos(10))
+    );
+    assert_eq!(
+        timeout(r#""10us""#).unwrap(),
+        Some(Duration::from_micros(10))
+    );
+    assert_eq!(
+        timeout(r#""1.5s""#).unwrap(),
+        Some(Duration::from_millis(1500))
+    );
+    assert_eq!(timeout(r#""2m""#).unwrap(), Some(Duration::from_secs(120)));
+    assert_eq!(
+        timeout(r#""0.5h""#).unwrap(),
+        Some(Duration::from_secs(1800))
+    );
+    assert!(timeout("-1").is_err());
+    assert!(timeout(r#""""#).is_err());
+    assert!(timeout(r#""1.2.3s""#).is_err());
+    assert!(timeout(r#""10 days""#).is_err());
+    assert!(timeout(r#""99999999999999999999h""#).is_err());
+    assert!(timeout(r#""94522879700260684295381835.9h""#).is_err());
+}
+
+#[test]
+fn roundtrip() {
+    let mut cfg = PoolConfig::new(8);
+    cfg.timeout = Some(Duration::from_micros(1500));
+    cfg.leak_threshold = Some(Duration::from_secs(60));
+    cfg.queue_mode = QueueMode::per_cpu();
+
+    let toml = toml::to_string(&cfg).unwrap();
+    assert!(toml.contains(r#"timeout = "1500us""#), "{}", toml);
+    assert!(toml.contains(r#"leak_threshold = "60s""#), "{}", toml);
+    let parsed: PoolConfig = toml::from_str(&toml).unwrap();
+    assert_eq!(parsed.timeout, cfg.timeout);
+    assert_eq!(parsed.leak_threshold, cfg.leak_threshold);
+    assert_eq!(parsed.queue_mode, cfg.queue_mode);
+
+    let json = serde_json::to_string(&PoolConfig::new(8)).unwrap();
+    let parsed: PoolConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.timeout, None);
+    assert_eq!(parsed.queue_mode, QueueMode::Single);
+}
@@ -3,10 +3,11 @@ use std::{convert::Infallible, time::Duration};
 use async_trait::async_trait;
 use tokio::time;
 
-use deadpool::{Object, PoolError};
+use deadpool::{BuildError, Object, PoolError, QueueMode};
 
 type Pool = deadpool::Pool<Manager>;
 
+#[derive(Debug)]
 struct Manager {}
 
 #[async_trait]
@@ -26,7 +27,7 @@ impl deadpool::Manager for Manager {
 #[tokio::test]
 async fn basic() {
     let mgr = Manager {};
-    let pool = Pool::builder(mgr).max_size(16).build();
+    let pool = Pool::builder(mgr).max_size(16).build().unwrap();
 
     let status = pool.status();
     assert_eq!(status.size, 0);
@@ -66,7 +67,7 @@ async fn basic() {
 #[tokio::test]
 async fn closing() {
     let mgr = Manager {};
-    let pool = Pool::builder(mgr).max_size(1).build();
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
 
     // fetch the only object from the pool
     let obj = pool.get().await;
@@ -97,7 +98,7 @@ async fn closing() {
 #[tokio::test]
 async fn close_resize() {
     let mgr = Manager {};
-    let pool = Pool::builder(mgr).max_size(1).build();
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
     pool.close().await;
     assert_eq!(pool.status().size, 0);
     assert_eq!(pool.status().max_size, 1);
@@ -106,7 +107,7 @@ async fn close_resize() {
 #[tokio::test(flavor = "multi_thread")]
 async fn concurrent() {
     let mgr = Manager {};
-    let pool = Pool::builder(mgr).max_size(3).build();
+    let pool = Pool::builder(mgr).max_size(3).build().unwrap();
 
     // Spawn tasks
     let futures = (0..100)
@@ -142,7 +143,7 @@ async fn concurrent() {
 #[tokio::test(flavor = "multi_thread")]
 async fn object_take() {
     let mgr = Manager {};
-    let pool = Pool::builder(mgr).max_size(2).build();
+    let pool = Pool::builder(mgr).max_size(2).build().unwrap();
     let obj0 = pool.get().await.unwrap();
     let obj1 = pool.get().await.unwrap();
 
@@ -172,3 +173,156 @@ async fn object_take() {
     assert_eq!(status.size, 2);
     assert_eq!(status.available, 2);
 }
+
+#[tokio::test]
+async fn object_discard() {
+    let mgr = Manager {};
+    let pool = Pool::builder(mgr).max_size(2).build().unwrap();
+    let obj0 = pool.get().await.unwrap();
+    let mut obj1 = pool.get().await.unwrap();
+
+    Object::discard(obj0);
+    let status = pool.status();
+    assert_eq!(status.size, 0);
+    assert_eq!(status.available, 1);
+
+    Object::mark_broken(&mut obj1);
+    drop(obj1);
+    let status = pool.status();
+    assert_eq!(status.size, 0);
+    assert_eq!(status.available, 2);
+    assert_eq!(pool.metrics().discard_count(), 2);
+
+    drop(pool.get().await.unwrap());
+    let status = pool.status();
+    assert_eq!(status.size, 1);
+    assert_eq!(status.available, 2);
+    assert_eq!(pool.metrics().discard_count(), 2);
+}
+
+#[tokio::test]
+async fn add() {
+    let mgr = Manager {};
+    let pool = Pool::builder(mgr).max_size(2).build().unwrap();
+
+    pool.add(42).unwrap();
+    let status = pool.status();
+    assert_eq!(status.size, 1);
+    assert_eq!(status.available, 2);
+
+    // Objects in use or being created count towards the maximum size.
+    let obj = pool.get().await.unwrap();
+    assert_eq!(*obj, 42);
+    let _obj = pool.get().await.unwrap();
+    assert_eq!(pool.add(1), Err(1));
+
+    // Taking and adding an object is the same as returning it.
+    let obj = Object::take(obj);
+    pool.add(obj).unwrap();
+    assert_eq!(pool.add(2), Err(2));
+    let status = pool.status();
+    assert_eq!(status.size, 1);
+    assert_eq!(status.available, 1);
+
+    pool.close().await;
+    assert_eq!(pool.add(3), Err(3));
+}
+
+#[tokio::test]
+async fn object_metrics() {
+    let mgr = Manager {};
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
+
+    let obj = pool.get().await.unwrap();
+    let metrics = *Object::metrics(&obj);
+    assert_eq!(metrics.recycle_count, 0);
+    assert!(metrics.recycled.is_none());
+    assert!(metrics.last_used.is_none());
+    drop(obj);
+
+    let obj = pool.get().await.unwrap();
+    let recycled = Object::metrics(&obj);
+    assert_eq!(recycled.created, metrics.created);
+    assert_eq!(recycled.recycle_count, 1);
+    assert!(recycled.recycled.is_some());
+    assert!(recycled.last_used.is_some());
+}
+
+#[tokio::test]
+async fn retain() {
+    let mgr = Manager {};
+    let pool = Pool::builder(mgr).max_size(4).build().unwrap();
+    {
+        let mut objs = Vec::new();
+        for i in 0..4 {
+            let mut obj = pool.get().await.unwrap();
+            *obj = i;
+            objs.push(obj);
+        }
+    }
+    assert_eq!(pool.status().size, 4);
+
+    pool.retain(|obj, _| obj % 2 == 0).await;
+    let status = pool.status();
+    assert_eq!(status.size, 2);
+    assert_eq!(status.available, 4);
+
+    pool.retain(|_, metrics| metrics.last_used.is_none()).await;
+    assert_eq!(pool.status().size, 0);
+}
+
+#[tokio::test]
+async fn build_errors() {
+    let build = |builder: deadpool::PoolBuilder<Manager>| builder.build().unwrap_err();
+    let builder = || Pool::builder(Manager {});
+
+    assert_eq!(build(builder().max_size(0)), BuildError::ZeroMaxSize);
+    assert_eq!(
+        build(builder().leak_threshold(Some(Duration::ZERO))),
+        BuildError::ZeroLeakThreshold
+    );
+    assert_eq!(
+        build(builder().queue_mode(QueueMode::Sharded { shards: 0 })),
+        BuildError::ZeroShards
+    );
+    assert_eq!(
+        build(builder().max_size(2).thread_cache(3)),
+        BuildError::ThreadCacheExceedsMaxSize {
+            thread_cache: 3,
+            max_size: 2
+        }
+    );
+    assert!(builder().timeout(Some(Duration::ZERO)).build().is_ok());
+}
+
+#[test]
+fn build_without_runtime() {
+    let builder = || Pool::builder(Manager {});
+    assert!(builder().build().is_ok());
+    assert!(builder().timeout(Some(Duration::ZERO)).build().is_ok());
+    assert_eq!(
+        builder()
+            .timeout(Some(Duration::from_secs(1)))
+            .build()
+            .unwrap_err(),
+        BuildError::NoRuntime
+    );
+    assert_eq!(
+        builder()
+            .leak_threshold(Some(Duration::from_secs(1)))
+            .build()
+            .unwrap_err(),
+        BuildError::NoRuntime
+    );
+
+    // Entering the runtime is enough to build the pool outside of it.
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let pool = {
+        let _guard = runtime.enter();
+        builder()
+            .timeout(Some(Duration::from_secs(1)))
+            .build()
+            .unwrap()
+    };
+    runtime.block_on(async { drop(pool.get().await.unwrap()) });
+}
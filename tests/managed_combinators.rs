@@ -0,0 +1,120 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+
+use deadpool::{CreateTimeoutError, ManagerExt, Pool, PoolError};
+
+#[derive(Default)]
+struct Manager {
+    created: AtomicUsize,
+}
+
+#[async_trait]
+impl deadpool::Manager for Manager {
+    type Type = usize;
+    type Error = ();
+
+    async fn create(&self) -> Result<usize, ()> {
+        Ok(self.created.fetch_add(1, Ordering::Relaxed))
+    }
+
+    async fn recycle(&self, conn: usize) -> Option<usize> {
+        Some(conn)
+    }
+}
+
+struct HangingManager;
+
+#[async_trait]
+impl deadpool::Manager for HangingManager {
+    type Type = ();
+    type Error = ();
+
+    async fn create(&self) -> Result<(), ()> {
+        std::future::pending().await
+    }
+
+    async fn recycle(&self, _: ()) -> Option<()> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn and_then_create() {
+    let mgr = Manager::default().and_then_create(|obj| async move { Ok(obj + 100) });
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
+    assert_eq!(*pool.get().await.unwrap(), 100);
+}
+
+#[tokio::test]
+async fn and_then_create_error() {
+    let mgr = Manager::default().and_then_create(|_| async move { Err(()) });
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
+    assert!(matches!(pool.get().await, Err(PoolError::Backend(()))));
+    assert_eq!(pool.status().available, 1);
+}
+
+#[tokio::test]
+async fn validate_with() {
+    let mgr = Manager::default().validate_with(|obj| async move {
+        if obj == 0 {
+            None
+        } else {
+            Some(obj)
+        }
+    });
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
+    drop(pool.get().await.unwrap());
+    assert_eq!(pool.status().size, 1);
+
+    // the first object fails validation and gets replaced
+    let obj = pool.get().await.unwrap();
+    assert_eq!(*obj, 1);
+    drop(obj);
+    assert_eq!(*pool.get().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn with_create_timeout() {
+    let mgr = HangingManager.with_create_timeout(Duration::from_millis(10));
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
+    assert!(matches!(
+        pool.get().await,
+        Err(PoolError::Backend(CreateTimeoutError::Timeout(_)))
+    ));
+    assert_eq!(pool.status().available, 1);
+}
+
+#[tokio::test]
+async fn with_recycle_timeout() {
+    let mgr = Manager::default()
+        .validate_with(|obj| async move {
+            if obj == 0 {
+                std::future::pending().await
+            } else {
+                Some(obj)
+            }
+        })
+        .with_recycle_timeout(Duration::from_millis(10));
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
+    drop(pool.get().await.unwrap());
+    assert_eq!(*pool.get().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn map_err() {
+    let mgr = HangingManager
+        .with_create_timeout(Duration::from_millis(10))
+        .map_err(|e| match e {
+            CreateTimeoutError::Timeout(d) => d,
+            CreateTimeoutError::Backend(()) => Duration::ZERO,
+        });
+    let pool = Pool::builder(mgr).max_size(1).build().unwrap();
+    assert!(matches!(
+        pool.get().await,
+        Err(PoolError::Backend(d)) if d == Duration::from_millis(10)
+    ));
+}
@@ -72,7 +72,7 @@ async fn pool_drained() {
     let manager = Manager::new();
     let mut rc = manager.remote_control.clone();
 
-    let pool = Pool::builder(manager).max_size(1).build();
+    let pool = Pool::builder(manager).max_size(1).build().unwrap();
     let pool_clone = pool.clone();
 
     // let first task grab the only connection
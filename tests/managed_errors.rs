@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use deadpool::{HookError, ManagerExt, NativeManager, PoolError, TimeoutType};
+
+type Pool = deadpool::Pool<Manager>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Error {
+    Refused,
+    Auth,
+}
+
+#[derive(Debug)]
+struct Manager {
+    error: Option<Error>,
+}
+
+#[async_trait]
+impl deadpool::Manager for Manager {
+    type Type = ();
+    type Error = Error;
+
+    async fn create(&self) -> Result<(), Error> {
+        self.error.map_or(Ok(()), Err)
+    }
+
+    async fn recycle(&self, obj: ()) -> Option<()> {
+        Some(obj)
+    }
+
+    fn is_transient(&self, error: &Error) -> bool {
+        *error == Error::Refused
+    }
+}
+
+#[tokio::test]
+async fn classification() {
+    let pool = |error| {
+        Pool::builder(Manager { error: Some(error) })
+            .max_size(1)
+            .build()
+            .unwrap()
+    };
+
+    let refused = pool(Error::Refused);
+    let err = refused.get().await.unwrap_err();
+    assert!(err.is_retryable(refused.manager()));
+    assert!(refused.is_retryable(&err));
+    assert!(!err.is_timeout());
+    assert!(!err.is_closed());
+
+    let auth = pool(Error::Auth);
+    let err = auth.get().await.unwrap_err();
+    assert!(!err.is_retryable(auth.manager()));
+    assert!(!auth.is_retryable(&err));
+
+    let manager = Manager { error: None };
+    assert!(PoolError::Timeout(TimeoutType::Wait).is_retryable(&manager));
+    assert!(!PoolError::Closed.is_retryable(&manager));
+    assert!(PoolError::PostCreateHook(HookError::Backend(Error::Refused)).is_retryable(&manager));
+    assert!(!PoolError::PostCreateHook(HookError::StaticMessage("broken")).is_retryable(&manager));
+}
+
+#[tokio::test]
+async fn timeout_and_closed() {
+    let pool = Pool::builder(Manager { error: None })
+        .max_size(1)
+        .build()
+        .unwrap();
+    let _obj = pool.get().await.unwrap();
+
+    let err = pool.timeout_get(Some(Duration::ZERO)).await.unwrap_err();
+    assert!(err.is_timeout());
+    assert!(err.is_retryable(pool.manager()));
+
+    pool.close().await;
+    let err = pool.get().await.unwrap_err();
+    assert!(err.is_closed());
+    assert!(!err.is_retryable(pool.manager()));
+}
+
+#[tokio::test]
+async fn combinators() {
+    let manager = Manager {
+        error: Some(Error::Refused),
+    }
+    .with_create_timeout(Duration::from_secs(1));
+    assert!(manager.is_transient(&deadpool::CreateTimeoutError::Timeout(Duration::ZERO)));
+    assert!(manager.is_transient(&deadpool::CreateTimeoutError::Backend(Error::Refused)));
+    assert!(!manager.is_transient(&deadpool::CreateTimeoutError::Backend(Error::Auth)));
+
+    let manager = Manager { error: None }.map_err(|e| e);
+    assert!(!manager.is_transient(&Error::Refused));
+}
+
+#[tokio::test]
+async fn map_backend() {
+    let pool = Pool::builder(Manager {
+        error: Some(Error::Auth),
+    })
+    .max_size(1)
+    .build()
+    .unwrap();
+    let err = pool
+        .get()
+        .await
+        .unwrap_err()
+        .map_backend(|e| format!("{e:?}"));
+    assert!(matches!(err, PoolError::Backend(e) if e == "Auth"));
+
+    let err = PoolError::PostCreateHook(HookError::Backend(Error::Refused)).map_backend(|_| 1);
+    assert!(matches!(
+        err,
+        PoolError::PostCreateHook(HookError::Backend(1))
+    ));
+    let err = PoolError::<Error>::Timeout(TimeoutType::Recycle).map_backend(|_| 1);
+    assert!(matches!(err, PoolError::Timeout(TimeoutType::Recycle)));
+}
+
+#[tokio::test]
+async fn boxed() {
+    async fn get(pool: &Pool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let obj = pool
+            .timeout_get(Some(Duration::ZERO))
+            .await
+            .map_err(|e| e.map_backend(|e| std::io::Error::other(format!("{e:?}"))))?;
+        drop(obj);
+        Ok(())
+    }
+
+    let pool = Pool::builder(Manager { error: None })
+        .max_size(1)
+        .build()
+        .unwrap();
+    let _obj = pool.get().await.unwrap();
+    let err = get(&pool).await.unwrap_err();
+    assert_eq!(TimeoutType::find(&*err), Some(TimeoutType::Wait));
+    assert_eq!(
+        err.to_string(),
+        "Timeout occurred while waiting for a slot to become available"
+    );
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        TimeoutType::Wait.to_string()
+    );
+
+    let err: Box<dyn std::error::Error + Send + Sync> = PoolError::<std::io::Error>::Closed.into();
+    assert_eq!(TimeoutType::find(&*err), None);
+}
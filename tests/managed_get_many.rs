@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use tokio::{task, time};
+
+use deadpool::{
+    testing::{assert_status, MockError, MockManager, Step},
+    PoolConfig, PoolError, TimeoutType,
+};
+
+type Pool = deadpool::Pool<MockManager>;
+
+#[tokio::test]
+async fn basic() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(4)
+        .build()
+        .unwrap();
+
+    let objs = pool.get_many(3, None).await.unwrap();
+    assert_eq!(objs.iter().map(|obj| **obj).collect::<Vec<_>>(), [0, 1, 2]);
+    assert_status(&pool).size(0).available(1);
+
+    drop(objs);
+    assert_status(&pool).size(3).available(4);
+
+    assert!(pool.get_many(0, None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn timeout_holds_nothing() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .build()
+        .unwrap();
+    let obj = pool.get().await.unwrap();
+
+    assert!(matches!(
+        pool.get_many(2, Some(Duration::from_millis(10))).await,
+        Err(PoolError::Timeout(TimeoutType::Wait))
+    ));
+    assert!(matches!(
+        pool.get_many(2, Some(Duration::ZERO)).await,
+        Err(PoolError::Timeout(TimeoutType::Wait))
+    ));
+    assert_status(&pool).available(1);
+
+    drop(obj);
+    assert_eq!(pool.get_many(2, None).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn error_returns_objects() {
+    let manager = MockManager::new();
+    manager.script_create([Step::Ok, Step::Err]);
+    let pool = Pool::builder(manager).max_size(3).build().unwrap();
+
+    assert!(matches!(
+        pool.get_many(3, None).await,
+        Err(PoolError::Backend(MockError))
+    ));
+    assert_status(&pool).size(1).available(3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn no_deadlock() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(3)
+        .build()
+        .unwrap();
+
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let pool = pool.clone();
+            task::spawn(async move {
+                for _ in 0..32 {
+                    let objs = pool.get_many(2, None).await.unwrap();
+                    task::yield_now().await;
+                    drop(objs);
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        time::timeout(Duration::from_secs(5), task)
+            .await
+            .expect("get_many deadlocked")
+            .unwrap();
+    }
+    assert_status(&pool).available(3);
+}
+
+#[tokio::test]
+async fn flushes_thread_cache() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .thread_cache(2)
+        .build()
+        .unwrap();
+    drop(pool.get_many(2, None).await.unwrap());
+
+    let objs = pool.get_many(2, Some(Duration::ZERO)).await.unwrap();
+    assert_eq!(objs.len(), 2);
+    assert_eq!(pool.manager().created(), 2);
+}
+
+#[tokio::test]
+async fn more_than_max_size() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .build()
+        .unwrap();
+    for timeout in [None, Some(Duration::ZERO)] {
+        assert!(matches!(
+            pool.get_many(3, timeout).await,
+            Err(PoolError::ExceedsMaxSize {
+                requested: 3,
+                max_size: 2
+            })
+        ));
+    }
+    assert_status(&pool).size(0).available(2);
+    assert_eq!(pool.manager().create_calls(), 0);
+
+    // A call which used to succeed fails once the pool has been shrunk.
+    let _ = pool.reconfigure(PoolConfig::new(1)).await.unwrap();
+    assert!(matches!(
+        pool.get_many(2, None).await,
+        Err(PoolError::ExceedsMaxSize {
+            requested: 2,
+            max_size: 1
+        })
+    ));
+}
+
+#[tokio::test]
+async fn huge() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .build()
+        .unwrap();
+    for n in [1 << 31, usize::MAX] {
+        assert!(matches!(
+            pool.get_many(n, None).await,
+            Err(PoolError::ExceedsMaxSize { requested, max_size: 2 }) if requested == n
+        ));
+    }
+    assert_status(&pool).size(0).available(2);
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use tokio::{task, time};
+
+use deadpool::{
+    testing::{assert_status, MockManager},
+    PoolError, TimeoutType,
+};
+
+type Pool = deadpool::Pool<MockManager>;
+
+async fn settle() {
+    time::sleep(Duration::from_millis(10)).await;
+}
+
+#[tokio::test]
+async fn longest_waiting_first() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+    let obj = pool.get().await.unwrap();
+
+    let first = task::spawn({
+        let pool = pool.clone();
+        async move { pool.get().await.unwrap() }
+    });
+    settle().await;
+    let second = task::spawn({
+        let pool = pool.clone();
+        async move { pool.get().await.unwrap() }
+    });
+    settle().await;
+
+    // The object skips the idle queue and keeps its permit.
+    drop(obj);
+    let obj = first.await.unwrap();
+    assert_eq!(*obj, 0);
+    assert_status(&pool).size(0).available(0);
+
+    settle().await;
+    assert!(!second.is_finished());
+    drop(obj);
+    assert_eq!(*second.await.unwrap(), 0);
+    assert_eq!(pool.manager().created(), 1);
+}
+
+#[tokio::test]
+async fn timed_out_waiter() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+    let obj = pool.get().await.unwrap();
+
+    assert!(matches!(
+        pool.timeout_get(Some(Duration::from_millis(10))).await,
+        Err(PoolError::Timeout(TimeoutType::Wait))
+    ));
+
+    // The object must not be handed to the task which gave up waiting.
+    drop(obj);
+    assert_status(&pool).size(1).available(1);
+    assert_eq!(*pool.get().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn close_wakes_waiters() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+    let obj = pool.get().await.unwrap();
+
+    let waiter = task::spawn({
+        let pool = pool.clone();
+        async move { pool.get().await }
+    });
+    settle().await;
+
+    pool.close().await;
+    assert!(matches!(waiter.await.unwrap(), Err(PoolError::Closed)));
+    drop(obj);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_handoff() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .build()
+        .unwrap();
+
+    let tasks: Vec<_> = (0..64)
+        .map(|_| {
+            let pool = pool.clone();
+            task::spawn(async move {
+                for _ in 0..16 {
+                    let timeout = Some(Duration::from_micros(50));
+                    if let Ok(obj) = pool.timeout_get(timeout).await {
+                        task::yield_now().await;
+                        drop(obj);
+                    }
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    // No object or permit got lost with cancelled waiters.
+    assert_status(&pool).size(2).available(2);
+}
@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+
+use deadpool::{Hook, HookError, PoolError};
+
+type Pool = deadpool::Pool<Manager>;
+
+struct Manager {}
+
+#[async_trait]
+impl deadpool::Manager for Manager {
+    type Type = usize;
+    type Error = ();
+
+    async fn create(&self) -> Result<usize, ()> {
+        Ok(0)
+    }
+
+    async fn recycle(&self, conn: usize) -> Option<usize> {
+        Some(conn)
+    }
+}
+
+#[tokio::test]
+async fn post_create_ok() {
+    let pool = Pool::builder(Manager {})
+        .post_create(Hook::sync_fn(|obj, _| {
+            *obj += 1;
+            Ok(())
+        }))
+        .post_create(Hook::async_fn(|obj, _| {
+            Box::pin(async move {
+                *obj *= 10;
+                Ok(())
+            })
+        }))
+        .build()
+        .unwrap();
+    assert_eq!(*pool.get().await.unwrap(), 10);
+}
+
+#[tokio::test]
+async fn post_create_err() {
+    let pool = Pool::builder(Manager {})
+        .max_size(1)
+        .post_create(Hook::sync_fn(|_, _| Err(HookError::StaticMessage("nope"))))
+        .build()
+        .unwrap();
+    assert!(matches!(
+        pool.get().await,
+        Err(PoolError::PostCreateHook(HookError::StaticMessage("nope")))
+    ));
+    let status = pool.status();
+    assert_eq!(status.size, 0);
+    assert_eq!(status.available, 1);
+    assert_eq!(pool.metrics().post_create_failure_count(), 1);
+    assert_eq!(pool.metrics().create_failure_count(), 0);
+}
+
+#[tokio::test]
+async fn recycle_hooks() {
+    let pool = Pool::builder(Manager {})
+        .max_size(1)
+        .pre_recycle(Hook::sync_fn(|obj, _| {
+            *obj += 1;
+            Ok(())
+        }))
+        .post_recycle(Hook::sync_fn(|obj, _| {
+            *obj += 10;
+            Ok(())
+        }))
+        .build()
+        .unwrap();
+    drop(pool.get().await.unwrap());
+    assert_eq!(*pool.get().await.unwrap(), 11);
+}
+
+#[tokio::test]
+async fn pre_recycle_err() {
+    let pool = Pool::builder(Manager {})
+        .max_size(1)
+        .pre_recycle(Hook::sync_fn(|_, _| {
+            Err(HookError::Message("broken".into()))
+        }))
+        .build()
+        .unwrap();
+    drop(pool.get().await.unwrap());
+    let status = pool.status();
+    assert_eq!(status.size, 1);
+
+    // the idle object gets discarded and a new one is created
+    let _obj = pool.get().await.unwrap();
+    let status = pool.status();
+    assert_eq!(status.size, 0);
+    assert_eq!(status.available, 0);
+    assert_eq!(pool.metrics().pre_recycle_failure_count(), 1);
+    assert_eq!(pool.metrics().recycle_failure_count(), 0);
+}
+
+#[tokio::test]
+async fn post_recycle_err() {
+    let pool = Pool::builder(Manager {})
+        .max_size(1)
+        .post_create(Hook::sync_fn(|obj, _| {
+            *obj = 1;
+            Ok(())
+        }))
+        .post_recycle(Hook::sync_fn(|obj, _| {
+            *obj = 2;
+            Err(HookError::Message("broken".into()))
+        }))
+        .build()
+        .unwrap();
+    drop(pool.get().await.unwrap());
+
+    // the recycled object is discarded, so a freshly created one is returned
+    assert_eq!(*pool.get().await.unwrap(), 1);
+    assert_eq!(pool.metrics().post_recycle_failure_count(), 1);
+}
+
+#[tokio::test]
+async fn hooks_receive_metrics() {
+    let pool = Pool::builder(Manager {})
+        .max_size(1)
+        .pre_recycle(Hook::sync_fn(|_, metrics| {
+            assert!(metrics.last_used.is_some());
+            Ok(())
+        }))
+        .post_recycle(Hook::sync_fn(|obj, metrics| {
+            *obj = metrics.recycle_count;
+            Ok(())
+        }))
+        .build()
+        .unwrap();
+    drop(pool.get().await.unwrap());
+    drop(pool.get().await.unwrap());
+    assert_eq!(*pool.get().await.unwrap(), 2);
+}
@@ -0,0 +1,100 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::time;
+
+use deadpool::PoolError;
+
+type Pool = deadpool::Pool<Manager>;
+
+struct Manager {}
+
+#[async_trait]
+impl deadpool::Manager for Manager {
+    type Type = usize;
+    type Error = Infallible;
+
+    async fn create(&self) -> Result<usize, Infallible> {
+        Ok(0)
+    }
+
+    async fn recycle(&self, conn: usize) -> Option<usize> {
+        Some(conn)
+    }
+}
+
+#[tokio::test]
+async fn leak_reported() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let pool = {
+        let reports = reports.clone();
+        Pool::builder(Manager {})
+            .max_size(2)
+            .leak_threshold(Some(Duration::from_millis(10)))
+            .on_leak(move |report| {
+                reports
+                    .lock()
+                    .unwrap()
+                    .push((report.location.line(), report.held_for));
+            })
+            .build()
+            .unwrap()
+    };
+
+    let line = line!() + 1;
+    let leaked = pool.get().await.unwrap();
+    let returned = pool.get().await.unwrap();
+    drop(returned);
+
+    time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(pool.metrics().leak_count(), 1);
+    assert_eq!(
+        *reports.lock().unwrap(),
+        vec![(line, Duration::from_millis(10))]
+    );
+    drop(leaked);
+}
+
+#[tokio::test]
+async fn leak_reported_with() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let pool = {
+        let reports = reports.clone();
+        Pool::builder(Manager {})
+            .max_size(1)
+            .leak_threshold(Some(Duration::from_millis(10)))
+            .on_leak(move |report| {
+                reports
+                    .lock()
+                    .unwrap()
+                    .push((report.location.file(), report.location.line()));
+            })
+            .build()
+            .unwrap()
+    };
+
+    let line = line!() + 2;
+    let res = pool
+        .with(|obj| {
+            Box::pin(async move {
+                time::sleep(Duration::from_millis(50)).await;
+                Ok::<_, PoolError<Infallible>>(*obj)
+            })
+        })
+        .await;
+    assert_eq!(res.unwrap(), 0);
+    assert_eq!(pool.metrics().leak_count(), 1);
+    assert_eq!(*reports.lock().unwrap(), vec![(file!(), line)]);
+}
+
+#[tokio::test]
+async fn leak_detection_disabled() {
+    let pool = Pool::builder(Manager {}).max_size(1).build().unwrap();
+    let _obj = pool.get().await.unwrap();
+    time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(pool.metrics().leak_count(), 0);
+}
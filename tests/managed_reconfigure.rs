@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use tokio::{task, time};
+
+use deadpool::{
+    testing::{assert_status, MockManager, Step},
+    BuildError, PoolConfig, PoolError, QueueMode, TimeoutType,
+};
+
+type Pool = deadpool::Pool<MockManager>;
+
+#[tokio::test]
+async fn grow() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+    let obj = pool.get().await.unwrap();
+
+    let waiter = task::spawn({
+        let pool = pool.clone();
+        async move { *pool.get().await.unwrap() }
+    });
+    task::yield_now().await;
+
+    let previous = pool.reconfigure(PoolConfig::new(2)).await.unwrap();
+    assert_eq!(previous.max_size, 1);
+    let obj2 = time::timeout(Duration::from_secs(1), waiter)
+        .await
+        .expect("waiting task has not been woken up")
+        .unwrap();
+    assert_eq!(obj2, 1);
+    drop(obj);
+    assert_status(&pool).max_size(2).size(2).available(2);
+}
+
+#[tokio::test]
+async fn shrink_idle() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(4)
+        .build()
+        .unwrap();
+    drop(pool.get_many(4, None).await.unwrap());
+    assert_status(&pool).max_size(4).size(4).available(4);
+
+    let _ = pool.reconfigure(PoolConfig::new(2)).await.unwrap();
+    assert_status(&pool).max_size(2).size(2).available(2);
+    assert_eq!(pool.get_many(2, None).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn shrink_in_use() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(3)
+        .build()
+        .unwrap();
+    let mut objs = pool.get_many(3, None).await.unwrap();
+
+    let _ = pool.reconfigure(PoolConfig::new(1)).await.unwrap();
+    assert_status(&pool).max_size(1).size(0).available(0);
+    assert!(matches!(
+        pool.timeout_get(Some(Duration::ZERO)).await,
+        Err(PoolError::Timeout(TimeoutType::Wait))
+    ));
+
+    drop(objs.pop());
+    drop(objs.pop());
+    assert_status(&pool).max_size(1).size(0).available(0);
+    drop(objs.pop());
+    assert_status(&pool).max_size(1).size(1).available(1);
+
+    let _ = pool.reconfigure(PoolConfig::new(2)).await.unwrap();
+    assert_eq!(pool.get_many(2, None).await.unwrap().len(), 2);
+    assert_eq!(pool.manager().created(), 4);
+}
+
+#[tokio::test]
+async fn shrink_while_recycling() {
+    let manager = MockManager::blocking();
+    manager.script_create([Step::Ok, Step::Ok]);
+    let pool = Pool::builder(manager.clone()).max_size(2).build().unwrap();
+    let obj = pool.get().await.unwrap();
+    drop(pool.get().await.unwrap());
+
+    // The waiting task holds a slot while recycling the idle object.
+    let waiter = task::spawn({
+        let pool = pool.clone();
+        async move { pool.timeout_get(Some(Duration::from_millis(100))).await }
+    });
+    time::sleep(Duration::from_millis(10)).await;
+    let _ = pool.reconfigure(PoolConfig::new(1)).await.unwrap();
+    manager.script_recycle([Step::Err]);
+
+    // There is no room for a new object until the other one is returned, so
+    // the task has to give up its slot and wait for another one.
+    let res = time::timeout(Duration::from_secs(1), waiter)
+        .await
+        .expect("waiting task ignored its timeout")
+        .unwrap();
+    assert!(matches!(res, Err(PoolError::Timeout(TimeoutType::Wait))));
+    assert_status(&pool).max_size(1).size(0).available(0);
+
+    drop(obj);
+    assert_status(&pool).max_size(1).size(1).available(1);
+}
+
+#[tokio::test]
+async fn timeout() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+    let _obj = pool.get().await.unwrap();
+
+    let mut config = PoolConfig::new(1);
+    config.timeout = Some(Duration::from_millis(10));
+    let _ = pool.reconfigure(config).await.unwrap();
+    assert!(matches!(
+        pool.get().await,
+        Err(PoolError::Timeout(TimeoutType::Wait))
+    ));
+}
+
+#[tokio::test]
+async fn thread_cache() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .thread_cache(1)
+        .build()
+        .unwrap();
+    drop(pool.get().await.unwrap());
+
+    let mut config = PoolConfig::new(2);
+    config.thread_cache = 0;
+    let previous = pool.reconfigure(config).await.unwrap();
+    assert_eq!(previous.thread_cache, 1);
+    assert_status(&pool).max_size(2).size(1).available(2);
+    drop(pool.get_many(2, None).await.unwrap());
+    assert_eq!(pool.manager().created(), 2);
+}
+
+#[tokio::test]
+async fn invalid() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        pool.reconfigure(PoolConfig::new(0)).await.unwrap_err(),
+        BuildError::ZeroMaxSize
+    );
+    let mut config = PoolConfig::new(2);
+    config.queue_mode = QueueMode::Sharded { shards: 2 };
+    assert_eq!(
+        pool.reconfigure(config).await.unwrap_err(),
+        BuildError::QueueModeChanged
+    );
+    assert_status(&pool).max_size(2).size(0).available(2);
+}
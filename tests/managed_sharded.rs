@@ -0,0 +1,81 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use tokio::task;
+
+use deadpool::{
+    testing::{assert_status, MockManager},
+    QueueMode,
+};
+
+type Pool = deadpool::Pool<MockManager>;
+
+fn sharded_pool(max_size: usize) -> Pool {
+    Pool::builder(MockManager::new())
+        .max_size(max_size)
+        .queue_mode(QueueMode::Sharded { shards: 8 })
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn steal_from_other_shard() {
+    let pool = sharded_pool(2);
+    let obj = pool.get().await.unwrap();
+
+    // Return the object from another thread and therefore to another shard.
+    thread::spawn(move || drop(obj)).join().unwrap();
+    assert_status(&pool).size(1).available(2);
+
+    assert_eq!(*pool.get().await.unwrap(), 0);
+    assert_eq!(pool.manager().created(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn max_size() {
+    let pool = sharded_pool(4);
+    let in_use = Arc::new(AtomicUsize::new(0));
+    let max_in_use = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = (0..64)
+        .map(|_| {
+            let pool = pool.clone();
+            let in_use = in_use.clone();
+            let max_in_use = max_in_use.clone();
+            task::spawn(async move {
+                for _ in 0..32 {
+                    let obj = pool.get().await.unwrap();
+                    let n = in_use.fetch_add(1, Ordering::SeqCst) + 1;
+                    let _ = max_in_use.fetch_max(n, Ordering::SeqCst);
+                    task::yield_now().await;
+                    let _ = in_use.fetch_sub(1, Ordering::SeqCst);
+                    drop(obj);
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert!(max_in_use.load(Ordering::SeqCst) <= 4);
+    let created = pool.manager().created();
+    assert!(created <= 4);
+    assert_status(&pool).max_size(4).size(created).available(4);
+}
+
+#[tokio::test]
+async fn close() {
+    let pool = sharded_pool(4);
+    let objs = [pool.get().await.unwrap(), pool.get().await.unwrap()];
+    thread::spawn(move || drop(objs)).join().unwrap();
+    assert_status(&pool).size(2);
+
+    pool.close().await;
+    assert_status(&pool).size(0);
+}
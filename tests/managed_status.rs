@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use tokio::{
+    task,
+    time::{self, Instant},
+};
+
+use deadpool::{
+    testing::{assert_status, MockManager, Step},
+    HighWaterMarks, Object,
+};
+
+type Pool = deadpool::Pool<MockManager>;
+
+#[tokio::test]
+async fn stages() {
+    let manager = MockManager::blocking();
+    let pool = Pool::builder(manager.clone()).max_size(1).build().unwrap();
+    assert_status(&pool)
+        .in_use(0)
+        .creating(0)
+        .recycling(0)
+        .waiting(0);
+
+    let get = || {
+        let pool = pool.clone();
+        task::spawn(async move { pool.get().await.unwrap() })
+    };
+
+    let creating = get();
+    task::yield_now().await;
+    assert_status(&pool)
+        .in_use(0)
+        .creating(1)
+        .recycling(0)
+        .waiting(0);
+
+    let waiting = get();
+    task::yield_now().await;
+    assert_status(&pool)
+        .in_use(0)
+        .creating(1)
+        .recycling(0)
+        .waiting(1);
+
+    manager.script_create([Step::Ok]);
+    let obj = creating.await.unwrap();
+    assert_status(&pool)
+        .in_use(1)
+        .creating(0)
+        .recycling(0)
+        .waiting(1);
+
+    drop(obj);
+    task::yield_now().await;
+    assert_status(&pool)
+        .in_use(0)
+        .creating(0)
+        .recycling(1)
+        .waiting(0);
+
+    manager.script_recycle([Step::Ok]);
+    let obj = waiting.await.unwrap();
+    assert_status(&pool)
+        .in_use(1)
+        .creating(0)
+        .recycling(0)
+        .waiting(0);
+
+    drop(obj);
+    assert_status(&pool)
+        .size(1)
+        .in_use(0)
+        .creating(0)
+        .recycling(0)
+        .waiting(0);
+}
+
+#[tokio::test]
+async fn cancelled() {
+    let manager = MockManager::blocking();
+    let pool = Pool::builder(manager.clone()).max_size(1).build().unwrap();
+
+    assert!(time::timeout(Duration::from_millis(10), pool.get())
+        .await
+        .is_err());
+    assert_status(&pool)
+        .in_use(0)
+        .creating(0)
+        .recycling(0)
+        .waiting(0);
+
+    manager.script_create([Step::Ok]);
+    let obj = pool.get().await.unwrap();
+    assert!(time::timeout(Duration::from_millis(10), pool.get())
+        .await
+        .is_err());
+    assert_status(&pool)
+        .in_use(1)
+        .creating(0)
+        .recycling(0)
+        .waiting(0);
+
+    assert_eq!(Object::take(obj), 0);
+    assert_status(&pool)
+        .in_use(0)
+        .creating(0)
+        .recycling(0)
+        .waiting(0);
+}
+
+#[tokio::test]
+async fn get_many() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .build()
+        .unwrap();
+    let objs = pool.get_many(2, None).await.unwrap();
+    assert_status(&pool).in_use(2).waiting(0);
+
+    let waiting = task::spawn({
+        let pool = pool.clone();
+        async move { pool.get_many(2, None).await.map(drop) }
+    });
+    task::yield_now().await;
+    assert_status(&pool).in_use(2).waiting(1);
+
+    drop(objs);
+    waiting.await.unwrap().unwrap();
+    assert_status(&pool)
+        .in_use(0)
+        .creating(0)
+        .recycling(0)
+        .waiting(0);
+}
+
+#[tokio::test]
+async fn high_water_marks() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(3)
+        .build()
+        .unwrap();
+    assert_eq!(pool.high_water_marks(), HighWaterMarks::default());
+
+    drop(pool.get_many(3, None).await.unwrap());
+    let obj = pool.get().await.unwrap();
+    let marks = pool.high_water_marks();
+    assert_eq!(marks.in_use, 3);
+    assert_eq!(marks.creating, 1);
+    assert_eq!(marks.recycling, 1);
+    assert_eq!(marks.waiting, 0);
+
+    assert_eq!(pool.reset_high_water_marks(), marks);
+    assert_eq!(
+        pool.high_water_marks(),
+        HighWaterMarks {
+            in_use: 1,
+            ..HighWaterMarks::default()
+        }
+    );
+    drop(obj);
+}
+
+#[tokio::test]
+async fn watch() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(2)
+        .build()
+        .unwrap();
+    let mut watch = pool.watch_status(Duration::ZERO);
+
+    let obj = pool.get().await.unwrap();
+    assert_eq!(watch.changed().await.unwrap().in_use, 1);
+
+    drop(obj);
+    let status = watch.changed().await.unwrap();
+    assert_eq!(status.in_use, 0);
+    assert_eq!(status.size, 1);
+
+    pool.close().await;
+    assert!(watch.changed().await.is_some());
+    assert!(watch.changed().await.is_none());
+
+    let mut watch = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap()
+        .watch_status(Duration::ZERO);
+    assert!(watch.changed().await.is_none());
+}
+
+#[tokio::test]
+async fn watch_throttled() {
+    let interval = Duration::from_millis(50);
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+    let mut watch = pool.watch_status(interval);
+
+    drop(pool.get().await.unwrap());
+    let start = Instant::now();
+    assert!(watch.changed().await.is_some());
+    for _ in 0..4 {
+        drop(pool.get().await.unwrap());
+    }
+    let status = watch.changed().await.unwrap();
+    assert!(start.elapsed() >= interval);
+    assert_eq!(status.in_use, 0);
+    assert_eq!(status.size, 1);
+
+    let watched = task::spawn(async move { watch.changed().await });
+    time::sleep(interval).await;
+    assert!(!watched.is_finished());
+    drop(pool.get().await.unwrap());
+    assert!(watched.await.unwrap().is_some());
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use tokio::{task, time};
+
+use deadpool::{
+    testing::{assert_status, MockManager},
+    PoolError,
+};
+
+type Pool = deadpool::Pool<MockManager>;
+
+fn cached_pool(max_size: usize) -> Pool {
+    Pool::builder(MockManager::new())
+        .max_size(max_size)
+        .thread_cache(1)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn reuse() {
+    let pool = cached_pool(2);
+    drop(pool.get().await.unwrap());
+
+    // The cached object kept its permit but is reported as available.
+    assert_status(&pool).size(1).available(2);
+
+    assert_eq!(*pool.get().await.unwrap(), 0);
+    drop(pool.get().await.unwrap());
+    assert_eq!(pool.manager().created(), 1);
+}
+
+#[tokio::test]
+async fn handoff_to_waiter() {
+    let pool = cached_pool(1);
+    let obj = pool.get().await.unwrap();
+
+    let waiter = task::spawn({
+        let pool = pool.clone();
+        async move { pool.timeout_get(Some(Duration::from_secs(1))).await }
+    });
+    time::sleep(Duration::from_millis(10)).await;
+
+    drop(obj);
+    assert_eq!(*waiter.await.unwrap().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn steal_from_other_thread() {
+    let pool = cached_pool(1);
+    let obj = pool.get().await.unwrap();
+    std::thread::spawn(move || drop(obj)).join().unwrap();
+
+    let obj = pool.timeout_get(Some(Duration::ZERO)).await.unwrap();
+    assert_eq!(*obj, 0);
+}
+
+#[tokio::test]
+async fn close() {
+    let pool = cached_pool(1);
+    drop(pool.get().await.unwrap());
+
+    pool.close().await;
+    assert_status(&pool).size(0);
+    assert!(matches!(pool.get().await, Err(PoolError::Closed)));
+}
+
+#[tokio::test]
+async fn retain() {
+    let pool = cached_pool(1);
+    drop(pool.get().await.unwrap());
+
+    pool.retain(|_, _| false).await;
+    assert_status(&pool).size(0).available(1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn concurrent() {
+    let pool = cached_pool(4);
+
+    let tasks: Vec<_> = (0..64)
+        .map(|_| {
+            let pool = pool.clone();
+            task::spawn(async move {
+                for _ in 0..32 {
+                    let timeout = Some(Duration::from_micros(50));
+                    if let Ok(obj) = pool.timeout_get(timeout).await {
+                        task::yield_now().await;
+                        drop(obj);
+                    }
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let created = pool.manager().created();
+    assert!(created <= 4);
+    assert_status(&pool).size(created).available(4);
+}
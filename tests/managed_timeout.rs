@@ -2,7 +2,7 @@ use std::{convert::Infallible, time::Duration};
 
 use async_trait::async_trait;
 
-use deadpool::{PoolConfig, PoolError};
+use deadpool::{PoolConfig, PoolError, QueueMode};
 
 type Pool = deadpool::Pool<Manager>;
 
@@ -28,8 +28,11 @@ async fn test_managed_timeout() {
     let cfg = PoolConfig {
         max_size: 16,
         timeout: Some(Duration::from_millis(0)),
+        leak_threshold: None,
+        queue_mode: QueueMode::Single,
+        thread_cache: 0,
     };
-    let pool = Pool::builder(mgr).config(cfg).build();
+    let pool = Pool::builder(mgr).config(cfg).build().unwrap();
 
     assert!(matches!(pool.get().await, Err(PoolError::Timeout(_))));
 }
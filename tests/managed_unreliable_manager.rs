@@ -3,6 +3,8 @@ use std::time::Duration;
 use async_trait::async_trait;
 use tokio::time;
 
+use deadpool::PoolError;
+
 type Pool = deadpool::Pool<Manager>;
 
 struct Manager {
@@ -38,9 +40,9 @@ async fn create() {
         recycle_fail: false,
     };
 
-    let pool = Pool::builder(manager).max_size(16).build();
+    let pool = Pool::builder(manager).max_size(16).build().unwrap();
     {
-        assert!(pool.get().await.is_err());
+        assert!(matches!(pool.get().await, Err(PoolError::Backend(()))));
     }
 
     let status = pool.status();
@@ -55,6 +57,7 @@ async fn create() {
     let status = pool.status();
     assert_eq!(status.available, 16);
     assert_eq!(status.size, 0);
+    assert_eq!(pool.metrics().create_failure_count(), 2);
 }
 
 #[tokio::test]
@@ -64,7 +67,7 @@ async fn recycle() {
         recycle_fail: true,
     };
 
-    let pool = Pool::builder(manager).max_size(16).build();
+    let pool = Pool::builder(manager).max_size(16).build().unwrap();
     {
         let _a = pool.get().await.unwrap();
         let _b = pool.get().await.unwrap();
@@ -84,4 +87,7 @@ async fn recycle() {
     let status = pool.status();
     assert_eq!(status.available, 16);
     assert_eq!(status.size, 1);
+    // Both idle objects failed to recycle before a new one was created.
+    assert_eq!(pool.metrics().recycle_failure_count(), 2);
+    assert_eq!(pool.metrics().create_failure_count(), 0);
 }
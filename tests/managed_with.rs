@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use tokio::{task, time};
+
+use deadpool::{
+    testing::{assert_metrics, assert_status, MockError, MockManager},
+    PoolError,
+};
+
+type Pool = deadpool::Pool<MockManager>;
+
+#[derive(Debug)]
+enum Error {
+    Pool(PoolError<MockError>),
+    Work,
+}
+
+impl From<PoolError<MockError>> for Error {
+    fn from(e: PoolError<MockError>) -> Self {
+        Self::Pool(e)
+    }
+}
+
+#[tokio::test]
+async fn ok_returns_object() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+
+    let res = pool
+        .with(|obj| {
+            Box::pin(async move {
+                *obj += 1;
+                Ok::<_, Error>(*obj)
+            })
+        })
+        .await;
+    assert_eq!(res.unwrap(), 1);
+    assert_status(&pool).size(1).available(1);
+    assert_eq!(*pool.get().await.unwrap(), 1);
+    assert_eq!(pool.manager().created(), 1);
+}
+
+#[tokio::test]
+async fn err_discards_object() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+
+    let res = pool
+        .with(|obj| {
+            Box::pin(async move {
+                *obj += 1;
+                Err::<(), _>(Error::Work)
+            })
+        })
+        .await;
+    assert!(matches!(res, Err(Error::Work)));
+    assert_status(&pool).size(0).available(1);
+    assert_metrics(&pool).discard_count(1);
+    // The discarded object has been replaced by a new one.
+    assert_eq!(*pool.get().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn panic_discards_object() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+
+    let res: Result<Result<(), Error>, _> = task::spawn({
+        let pool = pool.clone();
+        async move {
+            pool.with(|_| Box::pin(async { panic!("broken protocol") }))
+                .await
+        }
+    })
+    .await;
+    assert!(res.unwrap_err().is_panic());
+    assert_status(&pool).size(0).available(1);
+}
+
+#[tokio::test]
+async fn cancel_discards_object() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+
+    let res = time::timeout(
+        Duration::from_millis(10),
+        pool.with(|_| Box::pin(std::future::pending::<Result<(), Error>>())),
+    )
+    .await;
+    assert!(res.is_err());
+    assert_status(&pool).size(0).available(1);
+}
+
+#[tokio::test]
+async fn pool_error() {
+    let pool = Pool::builder(MockManager::new())
+        .max_size(1)
+        .build()
+        .unwrap();
+    pool.close().await;
+
+    let res = pool.with(|_| Box::pin(async { Ok::<_, Error>(()) })).await;
+    assert!(matches!(res, Err(Error::Pool(PoolError::Closed))));
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use tokio::{task, time};
+
+use deadpool::{
+    testing::{assert_metrics, assert_status, MockError, MockManager, Step},
+    Pool, PoolError, TimeoutType,
+};
+
+#[tokio::test]
+async fn scripted_create() {
+    let manager = MockManager::new();
+    manager.script_create([Step::Err, Step::Delay(Duration::from_millis(1)), Step::Ok]);
+    let pool = Pool::builder(manager.clone()).max_size(2).build().unwrap();
+
+    assert!(matches!(
+        pool.get().await,
+        Err(PoolError::Backend(MockError))
+    ));
+    let obj0 = pool.get().await.unwrap();
+    let obj1 = pool.get().await.unwrap();
+    assert_eq!((*obj0, *obj1), (0, 1));
+    assert_status(&pool).max_size(2).size(0).available(0);
+    assert_metrics(&pool)
+        .failure_count(1)
+        .create_failure_count(1)
+        .recycle_failure_count(0);
+    assert_eq!(manager.create_calls(), 3);
+    assert_eq!(manager.created(), 2);
+}
+
+#[tokio::test]
+async fn scripted_recycle() {
+    let manager = MockManager::new();
+    manager.script_recycle([Step::Err]);
+    let pool = Pool::builder(manager.clone()).max_size(1).build().unwrap();
+
+    drop(pool.get().await.unwrap());
+    assert_status(&pool).size(1).available(1);
+
+    // the first recycle fails, so a new object gets created
+    assert_eq!(*pool.get().await.unwrap(), 1);
+    assert_eq!(manager.recycle_calls(), 1);
+    assert_metrics(&pool)
+        .failure_count(0)
+        .recycle_failure_count(1);
+
+    assert_eq!(*pool.get().await.unwrap(), 1);
+    assert_eq!(manager.recycle_calls(), 2);
+}
+
+#[tokio::test]
+async fn hang() {
+    let manager = MockManager::new();
+    manager.script_create([Step::Hang]);
+    let pool = Pool::builder(manager)
+        .max_size(1)
+        .timeout(Some(Duration::from_millis(10)))
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        pool.get().await,
+        Err(PoolError::Timeout(TimeoutType::Create))
+    ));
+    assert_status(&pool).size(0).available(1);
+}
+
+#[tokio::test]
+async fn panic() {
+    let manager = MockManager::new();
+    manager.script_create([Step::Panic]);
+    let pool = Pool::builder(manager).max_size(1).build().unwrap();
+
+    let handle = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.get().await.map(|_| ()) })
+    };
+    assert!(handle.await.unwrap_err().is_panic());
+    assert_status(&pool).size(0).available(1);
+    assert!(pool.get().await.is_ok());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn blocking() {
+    let manager = MockManager::blocking();
+    let pool = Pool::builder(manager.clone()).max_size(1).build().unwrap();
+
+    let get = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.get().await.map(|_| ()) })
+    };
+    task::yield_now().await;
+    assert_eq!(manager.create_calls(), 1);
+    assert_status(&pool).size(0).available(0);
+
+    manager.script_create([Step::Ok]);
+    let result = time::timeout(Duration::from_millis(10), get).await;
+    assert!(result.unwrap().unwrap().is_ok());
+    assert_status(&pool).size(1).available(1);
+}